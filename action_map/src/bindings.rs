@@ -0,0 +1,274 @@
+use std::{collections::BTreeMap, error::Error, fmt, fs, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Action;
+
+/// Which way a gamepad axis has to be pushed to trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// A single physical input which can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    GamepadAxis(GamepadAxis, AxisDirection),
+    /// A button of one gamepad only, counting from 0 in the order the gamepads were connected,
+    /// so several players can each have their own
+    PadButton(usize, GamepadButton),
+    /// An axis of one gamepad only, counted like [`Binding::PadButton`]
+    PadAxis(usize, GamepadAxis, AxisDirection),
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Binding::GamepadButton(button) => write!(f, "Pad {button:?}"),
+            Binding::GamepadAxis(axis, AxisDirection::Positive) => write!(f, "Pad {axis:?}+"),
+            Binding::GamepadAxis(axis, AxisDirection::Negative) => write!(f, "Pad {axis:?}-"),
+            Binding::PadButton(pad, button) => write!(f, "Pad {} {button:?}", pad + 1),
+            Binding::PadAxis(pad, axis, AxisDirection::Positive) => {
+                write!(f, "Pad {} {axis:?}+", pad + 1)
+            }
+            Binding::PadAxis(pad, axis, AxisDirection::Negative) => {
+                write!(f, "Pad {} {axis:?}-", pad + 1)
+            }
+        }
+    }
+}
+
+/// A binding used by more than one action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict<A> {
+    pub binding: Binding,
+    pub actions: Vec<A>,
+}
+
+/// The bindings of every action of a game
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "A: Action")]
+pub struct ActionMap<A: Action> {
+    bindings: BTreeMap<A, Vec<Binding>>,
+}
+
+impl<A: Action> Default for ActionMap<A> {
+    fn default() -> Self {
+        ActionMap {
+            bindings: A::ALL
+                .iter()
+                .map(|&action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl<A: Action> ActionMap<A> {
+    /// The inputs bound to `action`
+    pub fn bindings(&self, action: A) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add `binding` to the inputs triggering `action`
+    pub fn bind(&mut self, action: A, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Remove every input bound to `action`
+    pub fn clear(&mut self, action: A) {
+        self.bindings.entry(action).or_default().clear();
+    }
+
+    /// Every binding which triggers more than one action
+    pub fn conflicts(&self) -> Vec<Conflict<A>> {
+        let mut actions_by_binding: Vec<Conflict<A>> = Vec::new();
+        for (&action, bindings) in &self.bindings {
+            for &binding in bindings {
+                match actions_by_binding.iter_mut().find(|c| c.binding == binding) {
+                    Some(conflict) => conflict.actions.push(action),
+                    None => actions_by_binding.push(Conflict {
+                        binding,
+                        actions: vec![action],
+                    }),
+                }
+            }
+        }
+
+        actions_by_binding.retain(|c| c.actions.len() > 1);
+        actions_by_binding
+    }
+
+    pub(crate) fn warn_conflicts(&self) {
+        for conflict in self.conflicts() {
+            warn!(
+                "{} is bound to several actions: {:?}",
+                conflict.binding, conflict.actions
+            );
+        }
+    }
+
+    /// Load the bindings saved at `path`.
+    ///
+    /// The defaults are used if the file is missing or can't be read, and for any action the
+    /// file doesn't mention, so adding a new action doesn't require deleting old config files.
+    pub fn load_or_default(path: &Path) -> Self {
+        let mut action_map = ActionMap::default();
+
+        let saved = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => {
+                info!("No controls saved at {}, using defaults", path.display());
+                return action_map;
+            }
+        };
+
+        match ron::from_str::<ActionMap<A>>(&saved) {
+            Ok(loaded) => action_map.bindings.extend(loaded.bindings),
+            Err(error) => warn!("Ignoring invalid controls in {}: {error}", path.display()),
+        }
+        action_map
+    }
+
+    /// Save the bindings to `path`, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    enum TestAction {
+        Left,
+        Right,
+        Jump,
+    }
+
+    impl Action for TestAction {
+        const ALL: &'static [Self] = &[TestAction::Left, TestAction::Right, TestAction::Jump];
+
+        fn default_bindings(self) -> Vec<Binding> {
+            match self {
+                TestAction::Left => vec![Binding::Key(KeyCode::ArrowLeft)],
+                TestAction::Right => vec![Binding::Key(KeyCode::ArrowRight)],
+                TestAction::Jump => vec![Binding::Key(KeyCode::Space)],
+            }
+        }
+    }
+
+    /// A file only this test writes to, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("action_map_{}_{name}.ron", std::process::id()));
+            fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn assert_defaults(action_map: &ActionMap<TestAction>) {
+        for &action in TestAction::ALL {
+            assert_eq!(action_map.bindings(action), action.default_bindings());
+        }
+    }
+
+    #[test]
+    fn the_defaults_have_no_conflicts() {
+        assert!(ActionMap::<TestAction>::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn bindings_shared_by_actions_are_conflicts() {
+        let mut action_map = ActionMap::<TestAction>::default();
+        action_map.bind(TestAction::Jump, Binding::Key(KeyCode::ArrowLeft));
+        action_map.bind(TestAction::Right, Binding::Key(KeyCode::ArrowLeft));
+        // Binding the same input twice to one action isn't a conflict
+        action_map.bind(TestAction::Jump, Binding::Key(KeyCode::Space));
+        action_map.bind(TestAction::Left, Binding::Mouse(MouseButton::Left));
+
+        assert_eq!(
+            action_map.conflicts(),
+            [Conflict {
+                binding: Binding::Key(KeyCode::ArrowLeft),
+                actions: vec![TestAction::Left, TestAction::Right, TestAction::Jump],
+            }]
+        );
+
+        action_map.clear(TestAction::Left);
+        action_map.clear(TestAction::Right);
+        assert!(action_map.conflicts().is_empty());
+    }
+
+    #[test]
+    fn saved_bindings_are_loaded_over_the_defaults() {
+        let mut saved = ActionMap::<TestAction>::default();
+        saved.clear(TestAction::Jump);
+        saved.bind(TestAction::Jump, Binding::Key(KeyCode::KeyW));
+        let file = TempFile::new("saved", "");
+        saved.save(&file.0).unwrap();
+
+        let loaded = ActionMap::<TestAction>::load_or_default(&file.0);
+        assert_eq!(
+            loaded.bindings(TestAction::Jump),
+            [Binding::Key(KeyCode::KeyW)]
+        );
+        assert_eq!(
+            loaded.bindings(TestAction::Left),
+            TestAction::Left.default_bindings()
+        );
+    }
+
+    #[test]
+    fn actions_missing_from_the_file_keep_their_defaults() {
+        let file = TempFile::new("partial", "(bindings: {Jump: [Key(KeyW)]})");
+
+        let loaded = ActionMap::<TestAction>::load_or_default(&file.0);
+        assert_eq!(
+            loaded.bindings(TestAction::Jump),
+            [Binding::Key(KeyCode::KeyW)]
+        );
+        assert_eq!(
+            loaded.bindings(TestAction::Right),
+            TestAction::Right.default_bindings()
+        );
+    }
+
+    #[test]
+    fn invalid_or_missing_files_fall_back_to_the_defaults() {
+        for contents in ["", "not ron at all", "(bindings: {Fly: [Key(KeyW)]})"] {
+            let file = TempFile::new("invalid", contents);
+            assert_defaults(&ActionMap::load_or_default(&file.0));
+        }
+
+        let missing = TempFile::new("missing", "");
+        fs::remove_file(&missing.0).unwrap();
+        assert_defaults(&ActionMap::load_or_default(&missing.0));
+    }
+}
@@ -0,0 +1,82 @@
+//! Mapping of game actions to keyboard keys, mouse buttons and gamepad inputs.
+//!
+//! Each game defines an enum of its actions and implements [`Action`] for it. Adding an
+//! [`ActionMapPlugin`] for that enum loads the bindings from a config file, keeps an
+//! [`ActionState`] up to date every frame, and adds a settings menu to rebind the actions.
+
+use std::{fmt::Debug, hash::Hash, marker::PhantomData, path::PathBuf};
+
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Serialize, de::DeserializeOwned};
+
+pub mod bindings;
+mod menu;
+pub mod state;
+
+pub use bindings::{ActionMap, AxisDirection, Binding, Conflict};
+pub use state::ActionState;
+
+/// An input action of a game, like "move left" or "exit"
+pub trait Action:
+    Copy + Eq + Ord + Hash + Debug + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    /// Every action, in the order they are listed in the settings menu
+    const ALL: &'static [Self];
+
+    /// Bindings used when the config file doesn't exist or doesn't mention this action
+    fn default_bindings(self) -> Vec<Binding>;
+}
+
+/// Plugin loading an [`ActionMap`] for `A` and keeping its [`ActionState`] up to date
+pub struct ActionMapPlugin<A> {
+    config_path: PathBuf,
+    menu_key: KeyCode,
+    _action: PhantomData<fn() -> A>,
+}
+
+impl<A: Action> ActionMapPlugin<A> {
+    /// Load and save the bindings from `config_path`
+    pub fn new(config_path: impl Into<PathBuf>) -> Self {
+        ActionMapPlugin {
+            config_path: config_path.into(),
+            menu_key: KeyCode::F1,
+            _action: PhantomData,
+        }
+    }
+
+    /// Set the key opening the settings menu. It can't be rebound, so it is always reachable.
+    pub fn with_menu_key(self, menu_key: KeyCode) -> Self {
+        ActionMapPlugin { menu_key, ..self }
+    }
+}
+
+/// Where the bindings of `A` are persisted
+#[derive(Resource)]
+pub struct ActionMapConfig<A> {
+    pub path: PathBuf,
+    pub menu_key: KeyCode,
+    /// While disabled, the actions are never triggered and the settings menu is closed
+    pub enabled: bool,
+    _action: PhantomData<fn() -> A>,
+}
+
+impl<A: Action> Plugin for ActionMapPlugin<A> {
+    fn build(&self, app: &mut App) {
+        let action_map = ActionMap::<A>::load_or_default(&self.config_path);
+        action_map.warn_conflicts();
+
+        app.insert_resource(action_map)
+            .insert_resource(ActionMapConfig::<A> {
+                path: self.config_path.clone(),
+                menu_key: self.menu_key,
+                enabled: true,
+                _action: PhantomData,
+            })
+            .init_resource::<ActionState<A>>()
+            .add_systems(
+                PreUpdate,
+                state::update_action_state::<A>.after(InputSystem),
+            )
+            .add_plugins(menu::SettingsMenuPlugin::<A>::default());
+    }
+}
@@ -0,0 +1,378 @@
+//! A settings menu listing every action, which rebinds an action when it is clicked.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::{Action, ActionMap, ActionMapConfig, AxisDirection, Binding};
+
+/// Sticks have to be pushed this far to be picked up while rebinding, to ignore drift
+const CAPTURE_AXIS_THRESHOLD: f32 = 0.75;
+const CAPTURE_AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::LeftZ,
+    GamepadAxis::RightZ,
+];
+
+const MENU_FONT_SIZE: f32 = 18.0;
+const MENU_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const CONFLICT_TEXT_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+const MENU_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.9);
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const CAPTURING_BUTTON_COLOR: Color = Color::srgb(0.35, 0.35, 0.6);
+
+pub(crate) struct SettingsMenuPlugin<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for SettingsMenuPlugin<A> {
+    fn default() -> Self {
+        SettingsMenuPlugin(PhantomData)
+    }
+}
+
+impl<A: Action> Plugin for SettingsMenuPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsMenu<A>>().add_systems(
+            Update,
+            (
+                toggle_menu::<A>,
+                start_capture::<A>,
+                capture_binding::<A>,
+                update_menu_text::<A>,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Whether the settings menu is open, and which action is waiting for a new binding
+#[derive(Resource)]
+pub(crate) struct SettingsMenu<A> {
+    pub(crate) open: bool,
+    capturing: Option<A>,
+}
+
+impl<A> Default for SettingsMenu<A> {
+    fn default() -> Self {
+        SettingsMenu {
+            open: false,
+            capturing: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsMenuRoot<A>(PhantomData<fn() -> A>);
+
+/// Clicking this button starts rebinding the action
+#[derive(Component)]
+struct RebindButton<A>(A);
+
+/// Text listing the bindings of an action
+#[derive(Component)]
+struct BindingsText<A>(A);
+
+#[derive(Component)]
+struct ConflictsText<A>(PhantomData<fn() -> A>);
+
+fn toggle_menu<A: Action>(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    config: Res<ActionMapConfig<A>>,
+    mut menu: ResMut<SettingsMenu<A>>,
+    root_query: Query<Entity, With<SettingsMenuRoot<A>>>,
+) {
+    // Disabling the action map closes its menu, so it isn't left open over another game
+    let open = if !config.enabled {
+        false
+    } else if keyboard_input.just_pressed(config.menu_key) {
+        !menu.open
+    } else {
+        return;
+    };
+    if open == menu.open {
+        return;
+    }
+
+    menu.open = open;
+    menu.capturing = None;
+
+    if !menu.open {
+        for root in &root_query {
+            commands.entity(root).despawn();
+        }
+        return;
+    }
+
+    let text_font = TextFont {
+        font_size: MENU_FONT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            SettingsMenuRoot::<A>(PhantomData),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(10.0),
+                left: Val::Percent(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(MENU_BACKGROUND_COLOR),
+            // Draw the menu on top of the game's own UI
+            GlobalZIndex(10),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!(
+                    "Controls ({:?} to close)\n\
+                     Click an action, then press a key, mouse button or gamepad input to add it.\n\
+                     Backspace clears the action, Escape cancels.",
+                    config.menu_key
+                )),
+                text_font.clone(),
+                TextColor(MENU_TEXT_COLOR),
+            ));
+
+            for &action in A::ALL {
+                parent
+                    .spawn((
+                        Button,
+                        RebindButton(action),
+                        Node {
+                            padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                    ))
+                    .with_child((
+                        Text::default(),
+                        BindingsText(action),
+                        text_font.clone(),
+                        TextColor(MENU_TEXT_COLOR),
+                    ));
+            }
+
+            parent.spawn((
+                Text::default(),
+                ConflictsText::<A>(PhantomData),
+                text_font.clone(),
+                TextColor(CONFLICT_TEXT_COLOR),
+            ));
+        });
+}
+
+fn start_capture<A: Action>(
+    mut menu: ResMut<SettingsMenu<A>>,
+    button_query: Query<(&Interaction, &RebindButton<A>), Changed<Interaction>>,
+) {
+    for (interaction, RebindButton(action)) in &button_query {
+        if *interaction == Interaction::Pressed {
+            menu.capturing = Some(*action);
+        }
+    }
+}
+
+/// The gamepad an action is played with, if its bindings are for one gamepad only.
+///
+/// Falls back to the default bindings, so a cleared action keeps its gamepad.
+fn edited_pad<A: Action>(action_map: &ActionMap<A>, action: A) -> Option<usize> {
+    let pad = |bindings: &[Binding]| {
+        bindings.iter().find_map(|binding| match *binding {
+            Binding::PadButton(pad, _) | Binding::PadAxis(pad, _, _) => Some(pad),
+            _ => None,
+        })
+    };
+    pad(action_map.bindings(action)).or_else(|| pad(&action.default_bindings()))
+}
+
+/// The first key, mouse button, gamepad button or stick movement made while capturing.
+///
+/// `gamepads` are in the order they were connected. With a `pad`, only that gamepad is listened
+/// to, and its inputs are bound to it alone.
+fn captured_binding(
+    keyboard_input: &ButtonInput<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    gamepads: &[&Gamepad],
+    pad: Option<usize>,
+) -> Option<Binding> {
+    if let Some(&key) = keyboard_input.get_just_pressed().next() {
+        return Some(Binding::Key(key));
+    }
+
+    if let Some(&button) = mouse_input.get_just_pressed().next() {
+        return Some(Binding::Mouse(button));
+    }
+
+    for (index, gamepad) in gamepads.iter().enumerate() {
+        if pad.is_some_and(|pad| pad != index) {
+            continue;
+        }
+
+        if let Some(&button) = gamepad.get_just_pressed().next() {
+            return Some(match pad {
+                Some(pad) => Binding::PadButton(pad, button),
+                None => Binding::GamepadButton(button),
+            });
+        }
+
+        for axis in CAPTURE_AXES {
+            let value = gamepad.get(axis).unwrap_or(0.0);
+            let direction = if value >= CAPTURE_AXIS_THRESHOLD {
+                AxisDirection::Positive
+            } else if value <= -CAPTURE_AXIS_THRESHOLD {
+                AxisDirection::Negative
+            } else {
+                continue;
+            };
+            return Some(match pad {
+                Some(pad) => Binding::PadAxis(pad, axis, direction),
+                None => Binding::GamepadAxis(axis, direction),
+            });
+        }
+    }
+
+    None
+}
+
+fn capture_binding<A: Action>(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    button_query: Query<&Interaction, With<RebindButton<A>>>,
+    config: Res<ActionMapConfig<A>>,
+    mut menu: ResMut<SettingsMenu<A>>,
+    mut action_map: ResMut<ActionMap<A>>,
+) {
+    let Some(action) = menu.capturing else {
+        return;
+    };
+
+    // Gamepad entities are spawned as they connect
+    let mut gamepads: Vec<(Entity, &Gamepad)> = gamepads.iter().collect();
+    gamepads.sort_by_key(|&(entity, _)| entity);
+    let gamepads: Vec<&Gamepad> = gamepads.into_iter().map(|(_, gamepad)| gamepad).collect();
+
+    // The click on an action's button picks what to rebind, rather than being the new binding
+    let clicked_button = button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    let mouse_input = if clicked_button {
+        &ButtonInput::default()
+    } else {
+        &*mouse_input
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        menu.capturing = None;
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        action_map.clear(action);
+    } else if let Some(binding) = captured_binding(
+        &keyboard_input,
+        mouse_input,
+        &gamepads,
+        edited_pad(&action_map, action),
+    ) {
+        action_map.bind(action, binding);
+    } else {
+        return;
+    }
+
+    menu.capturing = None;
+    action_map.warn_conflicts();
+    if let Err(error) = action_map.save(&config.path) {
+        warn!(
+            "Failed to save controls to {}: {error}",
+            config.path.display()
+        );
+    }
+}
+
+fn update_menu_text<A: Action>(
+    menu: Res<SettingsMenu<A>>,
+    action_map: Res<ActionMap<A>>,
+    mut bindings_query: Query<(&mut Text, &BindingsText<A>), Without<ConflictsText<A>>>,
+    mut conflicts_query: Query<&mut Text, With<ConflictsText<A>>>,
+    mut button_query: Query<(&mut BackgroundColor, &RebindButton<A>)>,
+) {
+    if !menu.open || !(menu.is_changed() || action_map.is_changed()) {
+        return;
+    }
+
+    for (mut text, BindingsText(action)) in &mut bindings_query {
+        let bindings = if menu.capturing == Some(*action) {
+            match edited_pad(&action_map, *action) {
+                Some(pad) => format!("press an input, on pad {}...", pad + 1),
+                None => "press an input...".to_string(),
+            }
+        } else {
+            action_map
+                .bindings(*action)
+                .iter()
+                .map(Binding::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        text.0 = format!("{action:?}: {bindings}");
+    }
+
+    for (mut color, RebindButton(action)) in &mut button_query {
+        color.0 = if menu.capturing == Some(*action) {
+            CAPTURING_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+    }
+
+    for mut text in &mut conflicts_query {
+        text.0 = action_map
+            .conflicts()
+            .iter()
+            .map(|conflict| format!("{} is bound to {:?}", conflict.binding, conflict.actions))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_bindings_keep_the_pad_being_edited() {
+        let keyboard_input = ButtonInput::<KeyCode>::default();
+        let mut mouse_input = ButtonInput::<MouseButton>::default();
+        let first = Gamepad::default();
+        let mut second = Gamepad::default();
+        second.digital_mut().press(GamepadButton::South);
+        let gamepads = [&first, &second];
+
+        assert_eq!(
+            captured_binding(&keyboard_input, &mouse_input, &gamepads, None),
+            Some(Binding::GamepadButton(GamepadButton::South))
+        );
+        assert_eq!(
+            captured_binding(&keyboard_input, &mouse_input, &gamepads, Some(1)),
+            Some(Binding::PadButton(1, GamepadButton::South))
+        );
+        // Other players' gamepads are ignored
+        assert_eq!(
+            captured_binding(&keyboard_input, &mouse_input, &gamepads, Some(0)),
+            None
+        );
+
+        mouse_input.press(MouseButton::Right);
+        assert_eq!(
+            captured_binding(&keyboard_input, &mouse_input, &gamepads, Some(0)),
+            Some(Binding::Mouse(MouseButton::Right))
+        );
+    }
+}
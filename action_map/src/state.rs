@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{Action, ActionMap, ActionMapConfig, AxisDirection, Binding, menu::SettingsMenu};
+
+/// Analog inputs count as pressed once they are pushed at least this far
+const PRESS_THRESHOLD: f32 = 0.5;
+
+/// The current state of every action, updated from its bindings at the start of each frame
+#[derive(Resource)]
+pub struct ActionState<A: Action> {
+    values: HashMap<A, f32>,
+    previous: HashMap<A, f32>,
+}
+
+impl<A: Action> Default for ActionState<A> {
+    fn default() -> Self {
+        ActionState {
+            values: HashMap::new(),
+            previous: HashMap::new(),
+        }
+    }
+}
+
+impl<A: Action> ActionState<A> {
+    /// How strongly `action` is triggered, from 0.0 to 1.0.
+    ///
+    /// Buttons and keys are either 0.0 or 1.0, while sticks and triggers report how far they are
+    /// pushed. When several inputs are bound to the action, the strongest one is used.
+    pub fn value(&self, action: A) -> f32 {
+        self.values.get(&action).copied().unwrap_or(0.0)
+    }
+
+    /// Whether `action` is currently held
+    pub fn pressed(&self, action: A) -> bool {
+        self.value(action) >= PRESS_THRESHOLD
+    }
+
+    /// Whether `action` started being held this frame
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.pressed(action) && self.previous.get(&action).copied().unwrap_or(0.0) < PRESS_THRESHOLD
+    }
+
+    /// Whether `action` stopped being held this frame
+    pub fn just_released(&self, action: A) -> bool {
+        !self.pressed(action)
+            && self.previous.get(&action).copied().unwrap_or(0.0) >= PRESS_THRESHOLD
+    }
+}
+
+fn button_value(gamepad: &Gamepad, button: GamepadButton) -> f32 {
+    // Analog buttons like triggers report how far they are pressed
+    gamepad
+        .get(button)
+        .unwrap_or(gamepad.pressed(button) as u8 as f32)
+}
+
+fn axis_value(gamepad: &Gamepad, axis: GamepadAxis, direction: AxisDirection) -> f32 {
+    let value = gamepad.get(axis).unwrap_or(0.0);
+    match direction {
+        AxisDirection::Positive => value.max(0.0),
+        AxisDirection::Negative => (-value).max(0.0),
+    }
+}
+
+/// `gamepads` are in the order they were connected
+fn binding_value(
+    binding: Binding,
+    keyboard_input: &ButtonInput<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    gamepads: &[&Gamepad],
+) -> f32 {
+    match binding {
+        Binding::Key(key) => keyboard_input.pressed(key) as u8 as f32,
+        Binding::Mouse(button) => mouse_input.pressed(button) as u8 as f32,
+        Binding::GamepadButton(button) => gamepads
+            .iter()
+            .map(|gamepad| button_value(gamepad, button))
+            .fold(0.0, f32::max),
+        Binding::GamepadAxis(axis, direction) => gamepads
+            .iter()
+            .map(|gamepad| axis_value(gamepad, axis, direction))
+            .fold(0.0, f32::max),
+        Binding::PadButton(pad, button) => gamepads
+            .get(pad)
+            .map_or(0.0, |gamepad| button_value(gamepad, button)),
+        Binding::PadAxis(pad, axis, direction) => gamepads
+            .get(pad)
+            .map_or(0.0, |gamepad| axis_value(gamepad, axis, direction)),
+    }
+}
+
+pub(crate) fn update_action_state<A: Action>(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    action_map: Res<ActionMap<A>>,
+    config: Res<ActionMapConfig<A>>,
+    menu: Res<SettingsMenu<A>>,
+    mut state: ResMut<ActionState<A>>,
+) {
+    let state = &mut *state;
+    state.previous = std::mem::take(&mut state.values);
+
+    // Inputs go to the settings menu while it is open, rather than to the game
+    if menu.open || !config.enabled {
+        return;
+    }
+
+    // Gamepad entities are spawned as they connect
+    let mut gamepads: Vec<(Entity, &Gamepad)> = gamepads.iter().collect();
+    gamepads.sort_by_key(|&(entity, _)| entity);
+    let gamepads: Vec<&Gamepad> = gamepads.into_iter().map(|(_, gamepad)| gamepad).collect();
+
+    for &action in A::ALL {
+        let value = action_map
+            .bindings(action)
+            .iter()
+            .map(|&binding| binding_value(binding, &keyboard_input, &mouse_input, &gamepads))
+            .fold(0.0, f32::max);
+        state.values.insert(action, value.min(1.0));
+    }
+}
@@ -0,0 +1,120 @@
+use action_map::{Action, AxisDirection, Binding};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::components::PlayerId;
+
+// its own file, the launcher runs every game from the same directory
+pub const CONTROLS_PATH: &str = "config/ball-game-controls.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GameAction {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    P2MoveLeft,
+    P2MoveRight,
+    P2MoveUp,
+    P2MoveDown,
+    CycleMode,
+    ToggleOverlay,
+    CaptureFrames,
+    ExportScene,
+    ImportScene,
+    Exit,
+}
+
+impl Action for GameAction {
+    const ALL: &'static [GameAction] = &[
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::MoveUp,
+        GameAction::MoveDown,
+        GameAction::P2MoveLeft,
+        GameAction::P2MoveRight,
+        GameAction::P2MoveUp,
+        GameAction::P2MoveDown,
+        GameAction::CycleMode,
+        GameAction::ToggleOverlay,
+        GameAction::CaptureFrames,
+        GameAction::ExportScene,
+        GameAction::ImportScene,
+        GameAction::Exit,
+    ];
+
+    fn default_bindings(self) -> Vec<Binding> {
+        match self {
+            // player one has WASD and the first gamepad, player two the arrows and the second
+            GameAction::MoveLeft => vec![
+                Binding::Key(KeyCode::KeyA),
+                Binding::PadButton(0, GamepadButton::DPadLeft),
+                Binding::PadAxis(0, GamepadAxis::LeftStickX, AxisDirection::Negative),
+            ],
+            GameAction::MoveRight => vec![
+                Binding::Key(KeyCode::KeyD),
+                Binding::PadButton(0, GamepadButton::DPadRight),
+                Binding::PadAxis(0, GamepadAxis::LeftStickX, AxisDirection::Positive),
+            ],
+            GameAction::MoveUp => vec![
+                Binding::Key(KeyCode::KeyW),
+                Binding::PadButton(0, GamepadButton::DPadUp),
+                Binding::PadAxis(0, GamepadAxis::LeftStickY, AxisDirection::Positive),
+            ],
+            GameAction::MoveDown => vec![
+                Binding::Key(KeyCode::KeyS),
+                Binding::PadButton(0, GamepadButton::DPadDown),
+                Binding::PadAxis(0, GamepadAxis::LeftStickY, AxisDirection::Negative),
+            ],
+            GameAction::P2MoveLeft => vec![
+                Binding::Key(KeyCode::ArrowLeft),
+                Binding::PadButton(1, GamepadButton::DPadLeft),
+                Binding::PadAxis(1, GamepadAxis::LeftStickX, AxisDirection::Negative),
+            ],
+            GameAction::P2MoveRight => vec![
+                Binding::Key(KeyCode::ArrowRight),
+                Binding::PadButton(1, GamepadButton::DPadRight),
+                Binding::PadAxis(1, GamepadAxis::LeftStickX, AxisDirection::Positive),
+            ],
+            GameAction::P2MoveUp => vec![
+                Binding::Key(KeyCode::ArrowUp),
+                Binding::PadButton(1, GamepadButton::DPadUp),
+                Binding::PadAxis(1, GamepadAxis::LeftStickY, AxisDirection::Positive),
+            ],
+            GameAction::P2MoveDown => vec![
+                Binding::Key(KeyCode::ArrowDown),
+                Binding::PadButton(1, GamepadButton::DPadDown),
+                Binding::PadAxis(1, GamepadAxis::LeftStickY, AxisDirection::Negative),
+            ],
+            GameAction::CycleMode => vec![Binding::Key(KeyCode::F8)],
+            GameAction::ToggleOverlay => vec![Binding::Key(KeyCode::F3)],
+            GameAction::CaptureFrames => vec![Binding::Key(KeyCode::F4)],
+            GameAction::ExportScene => vec![Binding::Key(KeyCode::F6)],
+            GameAction::ImportScene => vec![Binding::Key(KeyCode::F7)],
+            GameAction::Exit => vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::GamepadButton(GamepadButton::Select),
+            ],
+        }
+    }
+}
+
+impl GameAction {
+    // left, right, up and down for `player`
+    pub fn moves(player: PlayerId) -> [GameAction; 4] {
+        match player {
+            PlayerId::One => [
+                GameAction::MoveLeft,
+                GameAction::MoveRight,
+                GameAction::MoveUp,
+                GameAction::MoveDown,
+            ],
+            PlayerId::Two => [
+                GameAction::P2MoveLeft,
+                GameAction::P2MoveRight,
+                GameAction::P2MoveUp,
+                GameAction::P2MoveDown,
+            ],
+        }
+    }
+}
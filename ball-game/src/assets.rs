@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+use common::AssetCollection;
+
+// every sprite of the game, loaded before playing
+#[derive(Resource)]
+pub struct BallGameAssets {
+    pub player: Handle<Image>,
+    pub enemy: Handle<Image>,
+    pub star: Handle<Image>,
+}
+
+impl AssetCollection for BallGameAssets {
+    fn load(asset_server: &AssetServer) -> Self {
+        BallGameAssets {
+            player: asset_server.load("sprites/ball_blue_large.png"),
+            enemy: asset_server.load("sprites/ball_red_large.png"),
+            star: asset_server.load("sprites/star.png"),
+        }
+    }
+
+    fn handles(&self) -> Vec<UntypedHandle> {
+        vec![
+            self.player.clone().untyped(),
+            self.enemy.clone().untyped(),
+            self.star.clone().untyped(),
+        ]
+    }
+}
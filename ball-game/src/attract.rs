@@ -0,0 +1,49 @@
+// the game playing itself, as the demo the launcher shows when nobody is at its menu
+//
+// while `Attract` holds the game's name, the potential field bot steers every player instead of
+// the inputs: it heads for the nearest star and away from the enemies about to run into it, see
+// `bot::policies`. everything else runs as usual, so a demo is also a smoke test of the whole
+// game, see `tests/demo.rs`. demo rounds aren't recorded, saved or put on the high scores, and
+// the next one starts when a round is over.
+
+use bevy::prelude::*;
+use common::Attract;
+
+use crate::bot::Bot;
+use crate::bot::policies::PotentialField;
+use crate::events::GameOver;
+use crate::{LOADING, NAME, STATE};
+
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(STATE), start_demo.run_if(demo_playing))
+            .add_systems(OnExit(STATE), stop_demo.run_if(demo_playing))
+            .add_systems(Update, restart_demo_on_game_over.run_if(in_state(STATE).and(demo_playing)));
+    }
+}
+
+// run condition of the demo, and of what it leaves out
+pub fn demo_playing(attract: Option<Res<Attract>>) -> bool {
+    attract.is_some_and(|attract| attract.0 == NAME)
+}
+
+// the potential field bot plays the demo
+pub fn start_demo(mut commands: Commands) {
+    commands.insert_resource(Bot::new(PotentialField::default()));
+}
+
+pub fn stop_demo(mut commands: Commands) {
+    commands.remove_resource::<Bot>();
+}
+
+// keep the demo going
+pub fn restart_demo_on_game_over(
+    mut game_over_event_reader: EventReader<GameOver>,
+    mut next_state: ResMut<NextState<common::AppState>>,
+) {
+    if game_over_event_reader.read().last().is_some() {
+        next_state.set(LOADING);
+    }
+}
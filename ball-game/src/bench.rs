@@ -0,0 +1,288 @@
+// headless benchmark: `cargo run -p ball-game --release --features bench -- --bench`
+//
+// runs the game without a window on a fixed time step and a fixed seed, with extra enemies
+// and stars, and prints the timings as JSON so runs can be compared across commits.
+// per-system timings come from the system spans of bevy_ecs, which the `bench` feature
+// turns on; without it only the totals are reported.
+
+use bevy::log::tracing::field::{Field, Visit};
+use bevy::log::tracing::span::{Attributes, Id};
+use bevy::log::tracing::{self, Subscriber};
+use bevy::log::tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use bevy::log::tracing_subscriber::registry::{LookupSpan, Registry};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::enemy::components::Enemy;
+use crate::replay::resources::ReplayMode;
+use crate::rng::GameRng;
+use crate::star::components::Star;
+use crate::assets::BallGameAssets;
+use crate::headless::{WINDOW_SIZE, enter_game, headless_app};
+use crate::STATE;
+
+// one tick of the benchmark, the game runs every system once per tick, on the fixed time step too
+const TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    pub enemies: usize,
+    pub stars: usize,
+    pub ticks: u32,
+    pub seed: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            enemies: 100,
+            stars: 100,
+            ticks: 1000,
+            seed: 0,
+        }
+    }
+}
+
+impl BenchConfig {
+    pub const USAGE: &str =
+        "usage: ball-game --bench [--enemies N] [--stars N] [--ticks N] [--seed N]";
+
+    // the arguments after `--bench`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = BenchConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            let invalid = |_| format!("invalid value for {arg}: {value}");
+            match arg.as_str() {
+                "--enemies" => config.enemies = value.parse().map_err(invalid)?,
+                "--stars" => config.stars = value.parse().map_err(invalid)?,
+                "--ticks" => config.ticks = value.parse().map_err(invalid)?,
+                "--seed" => config.seed = value.parse().map_err(invalid)?,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Serialize)]
+pub struct BenchReport {
+    pub seed: u64,
+    pub ticks: u32,
+    pub enemies_spawned: usize,
+    pub stars_spawned: usize,
+    pub final_enemies: usize,
+    pub final_stars: usize,
+    pub total_ms: f64,
+    pub ticks_per_second: f64,
+    // enemies and stars updated per second, summed over the ticks
+    pub entity_ticks_per_second: f64,
+    pub systems: Vec<SystemTiming>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SystemTiming {
+    pub name: String,
+    pub calls: u64,
+    pub total_ms: f64,
+    pub mean_us: f64,
+}
+
+// runs the benchmark and prints the report to stdout
+pub fn run(config: BenchConfig) {
+    let timings = SystemTimings::default();
+    let subscriber = Registry::default().with(timings.clone());
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        eprintln!("couldn't install the timing subscriber, system timings will be missing");
+    }
+    if !cfg!(feature = "bench") {
+        eprintln!("build with `--features bench` for per-system timings");
+    }
+
+    let report = measure(&config, &timings);
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(error) => eprintln!("couldn't write the report: {error}"),
+    }
+}
+
+fn measure(config: &BenchConfig, timings: &SystemTimings) -> BenchReport {
+    let mut app = bench_app(config.seed);
+    // load the assets and enter the game, which spawns its entities
+    enter_game(&mut app);
+    spawn_load(app.world_mut(), config);
+    app.update();
+    timings.clear();
+
+    let mut entity_ticks = 0;
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..config.ticks {
+        entity_ticks += count::<Enemy>(app.world_mut()) + count::<Star>(app.world_mut());
+        let start = Instant::now();
+        app.update();
+        elapsed += start.elapsed();
+    }
+
+    let total_secs = elapsed.as_secs_f64();
+    let per_second = |count: f64| if total_secs > 0.0 { count / total_secs } else { 0.0 };
+    BenchReport {
+        seed: config.seed,
+        ticks: config.ticks,
+        enemies_spawned: config.enemies,
+        stars_spawned: config.stars,
+        final_enemies: count::<Enemy>(app.world_mut()),
+        final_stars: count::<Star>(app.world_mut()),
+        total_ms: total_secs * 1000.0,
+        ticks_per_second: per_second(config.ticks as f64),
+        entity_ticks_per_second: per_second(entity_ticks as f64),
+        systems: timings.report(),
+    }
+}
+
+// the headless game, advancing by exactly one tick per update
+fn bench_app(seed: u64) -> App {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        // exactly one fixed tick per update
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        // the runs aren't recorded, and keep the seed of the benchmark
+        .insert_resource(ReplayMode::Off)
+        .insert_resource(GameRng::seeded(seed));
+
+    app.finish();
+    app.cleanup();
+    app
+}
+
+fn spawn_load(world: &mut World, config: &BenchConfig) {
+    let assets = world.resource::<BallGameAssets>();
+    let enemy_image = assets.enemy.clone();
+    let star_image = assets.star.clone();
+    let mut rng = world.remove_resource::<GameRng>().unwrap_or_default();
+
+    for _ in 0..config.enemies {
+        let position = random_position(&mut rng);
+        let direction = Vec2::new(rng.random::<f32>(), rng.random::<f32>()).normalize();
+        world.spawn((
+            Sprite::from_image(enemy_image.clone()),
+            Transform::from_translation(position.extend(0.0)),
+            Enemy { direction },
+            StateScoped(STATE),
+        ));
+    }
+    for _ in 0..config.stars {
+        let position = random_position(&mut rng);
+        world.spawn((
+            Sprite::from_image(star_image.clone()),
+            Transform::from_translation(position.extend(0.0)),
+            Star {},
+            StateScoped(STATE),
+        ));
+    }
+
+    world.insert_resource(rng);
+}
+
+fn random_position(rng: &mut GameRng) -> Vec2 {
+    Vec2::new(rng.random::<f32>(), rng.random::<f32>()) * WINDOW_SIZE
+}
+
+fn count<C: Component>(world: &mut World) -> usize {
+    world.query_filtered::<(), With<C>>().iter(world).count()
+}
+
+// time spent in each system, collected from the "system" spans of bevy_ecs
+#[derive(Clone, Default)]
+struct SystemTimings {
+    totals: Arc<Mutex<HashMap<String, (u64, Duration)>>>,
+}
+
+impl SystemTimings {
+    fn clear(&self) {
+        self.totals.lock().unwrap().clear();
+    }
+
+    // slowest first
+    fn report(&self) -> Vec<SystemTiming> {
+        let totals = self.totals.lock().unwrap();
+        let mut systems: Vec<SystemTiming> = totals
+            .iter()
+            .map(|(name, &(calls, total))| SystemTiming {
+                name: name.clone(),
+                calls,
+                total_ms: total.as_secs_f64() * 1000.0,
+                mean_us: total.as_secs_f64() * 1_000_000.0 / calls.max(1) as f64,
+            })
+            .collect();
+        systems.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        systems
+    }
+}
+
+// name of the system a span is for
+struct SystemName(String);
+
+// when the span was last entered
+struct Entered(Instant);
+
+#[derive(Default)]
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{value:?}").trim_matches('"').to_string());
+        }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SystemTimings {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "system" {
+            return;
+        }
+        let mut visitor = NameVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SystemName(name));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if extensions.get_mut::<SystemName>().is_some() {
+                extensions.replace(Entered(Instant::now()));
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let (Some(name), Some(entered)) =
+            (extensions.get::<SystemName>(), extensions.get::<Entered>())
+        {
+            let mut totals = self.totals.lock().unwrap();
+            let (calls, total) = totals.entry(name.0.clone()).or_default();
+            *calls += 1;
+            *total += entered.0.elapsed();
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// bots playing the game, for experimenting with agents
+//
+// a `Policy` is asked where to go every fixed tick. it sees an `Observation` of the world around
+// its player, and its answer goes through the players' intents like the inputs do, so bots play by
+// the same rules and their runs can be recorded and replayed. while the `Bot` resource exists it
+// steers every player, each with their own observation. the demo plays with one of the reference
+// `policies`, and the `runner` plays many rounds headless and reports how the scores spread.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::BallGameSet;
+use crate::enemy::components::Enemy;
+use crate::player::components::{Player, PlayerId};
+use crate::player::resources::{PlayerIntent, PlayerIntents};
+use crate::star::components::Star;
+
+pub mod policies;
+pub mod runner;
+
+// enemies further than this from the player are left out of its observation
+pub const NEARBY_DISTANCE: f32 = 300.0;
+
+// what a bot knows about the world on a tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub player: PlayerId,
+    pub position: Vec2,
+    pub lives: u32,
+    // the players stay inside it, with the origin in a corner
+    pub arena: Vec2,
+    // the nearby enemies, nearest first
+    pub enemies: Vec<EnemyObservation>,
+    // every star, nearest first
+    pub stars: Vec<Vec2>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnemyObservation {
+    pub position: Vec2,
+    // moving this way at `ENEMY_SPEED`
+    pub direction: Vec2,
+}
+
+// how a bot plays
+pub trait Policy: Send + Sync {
+    // the name the runner reports it under
+    fn name(&self) -> &str;
+
+    // the direction to move in this tick, as long as 1 at full speed, longer is cut down
+    fn act(&mut self, observation: &Observation) -> Vec2;
+
+    // a new round is starting
+    fn reset(&mut self) {}
+}
+
+// the policy steering every player instead of the inputs
+#[derive(Resource)]
+pub struct Bot(pub Box<dyn Policy>);
+
+impl Bot {
+    pub fn new(policy: impl Policy + 'static) -> Self {
+        Bot(Box::new(policy))
+    }
+}
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, steer_players.in_set(BallGameSet::Input).run_if(resource_exists::<Bot>))
+            .add_systems(OnEnter(crate::STATE), reset_bot.run_if(resource_exists::<Bot>));
+    }
+}
+
+// what the player at `position` sees of the enemies and stars
+pub fn observe(
+    player: &Player,
+    position: Vec2,
+    arena: Vec2,
+    enemies: impl IntoIterator<Item = EnemyObservation>,
+    stars: impl IntoIterator<Item = Vec2>,
+) -> Observation {
+    let by_distance = |a: &Vec2, b: &Vec2| a.distance_squared(position).total_cmp(&b.distance_squared(position));
+    let mut enemies: Vec<EnemyObservation> = enemies
+        .into_iter()
+        .filter(|enemy| enemy.position.distance(position) <= NEARBY_DISTANCE)
+        .collect();
+    enemies.sort_by(|a, b| by_distance(&a.position, &b.position));
+    let mut stars: Vec<Vec2> = stars.into_iter().collect();
+    stars.sort_by(by_distance);
+
+    Observation {
+        player: player.id,
+        position,
+        lives: player.lives,
+        arena,
+        enemies,
+        stars,
+    }
+}
+
+pub fn steer_players(
+    mut bot: ResMut<Bot>,
+    mut intents: ResMut<PlayerIntents>,
+    player_query: Query<(&Transform, &Player)>,
+    enemy_query: Query<(&Transform, &Enemy)>,
+    star_query: Query<&Transform, With<Star>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let window = window_query.single().expect("PrimaryWindow not found");
+    let arena = Vec2::new(window.width(), window.height());
+
+    *intents = PlayerIntents::default();
+    for (transform, player) in player_query.iter() {
+        let observation = observe(
+            player,
+            transform.translation.truncate(),
+            arena,
+            enemy_query.iter().map(|(enemy_transform, enemy)| EnemyObservation {
+                position: enemy_transform.translation.truncate(),
+                direction: enemy.direction,
+            }),
+            star_query.iter().map(|star_transform| star_transform.translation.truncate()),
+        );
+        let direction = bot.0.act(&observation);
+        intents.0[player.id.index()] = PlayerIntent::from_direction(direction.clamp_length_max(1.0));
+    }
+}
+
+pub fn reset_bot(mut bot: ResMut<Bot>) {
+    bot.0.reset();
+}
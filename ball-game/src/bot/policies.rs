@@ -0,0 +1,113 @@
+// the reference bots, as baselines for the ones being experimented with
+
+use bevy::prelude::*;
+
+use super::{Observation, Policy};
+use crate::enemy::{ENEMY_SIZE, ENEMY_SPEED};
+use crate::player::PLAYER_SIZE;
+
+// the names of the reference bots, as `from_name` takes them
+pub const POLICY_NAMES: [&str; 2] = [Greedy::NAME, PotentialField::NAME];
+
+// the reference bot called `name`
+pub fn from_name(name: &str) -> Option<Box<dyn Policy>> {
+    match name {
+        Greedy::NAME => Some(Box::new(Greedy)),
+        PotentialField::NAME => Some(Box::new(PotentialField::default())),
+        _ => None,
+    }
+}
+
+// straight for the nearest star, whatever is in the way
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Greedy;
+
+impl Greedy {
+    pub const NAME: &str = "greedy";
+}
+
+impl Policy for Greedy {
+    fn name(&self) -> &str {
+        Greedy::NAME
+    }
+
+    fn act(&mut self, observation: &Observation) -> Vec2 {
+        seek(observation)
+    }
+}
+
+// pulled to the nearest star and pushed away from the enemies about to run into the player, and
+// from the walls it could be cornered against. the demo plays with it
+#[derive(Debug, Clone, Copy)]
+pub struct PotentialField {
+    // how far ahead it looks at where the enemies are going, in seconds
+    pub lookahead: f32,
+    // room kept from the enemies on top of the collision radii
+    pub enemy_margin: f32,
+    // how much more getting out of the way matters than getting to the star
+    pub avoid_weight: f32,
+    // the walls push the player away when it is closer to them than this
+    pub wall_margin: f32,
+}
+
+impl PotentialField {
+    pub const NAME: &str = "potential";
+}
+
+impl Default for PotentialField {
+    fn default() -> Self {
+        PotentialField {
+            lookahead: 0.6,
+            enemy_margin: 40.0,
+            avoid_weight: 3.0,
+            wall_margin: 80.0,
+        }
+    }
+}
+
+impl Policy for PotentialField {
+    fn name(&self) -> &str {
+        PotentialField::NAME
+    }
+
+    fn act(&mut self, observation: &Observation) -> Vec2 {
+        let player = observation.position;
+        let danger = PLAYER_SIZE / 2.0 + ENEMY_SIZE / 2.0 + self.enemy_margin;
+        let mut avoid = Vec2::ZERO;
+        for enemy in &observation.enemies {
+            // where the enemy comes closest within the lookahead, if the player stood still
+            let offset = enemy.position - player;
+            let velocity = enemy.direction * ENEMY_SPEED;
+            let speed_squared = velocity.length_squared();
+            let time = if speed_squared > 0.0 {
+                (-offset.dot(velocity) / speed_squared).clamp(0.0, self.lookahead)
+            } else {
+                0.0
+            };
+            let closest = offset + velocity * time;
+            let distance = closest.length();
+            if distance >= danger {
+                continue;
+            }
+            // straight at the player, step aside instead
+            let away = (-closest).try_normalize().unwrap_or_else(|| velocity.perp().normalize_or_zero());
+            avoid += away * (1.0 - distance / danger);
+        }
+
+        let mut walls = Vec2::ZERO;
+        if self.wall_margin > 0.0 {
+            for axis in 0..2 {
+                let near = player[axis];
+                let far = observation.arena[axis] - player[axis];
+                walls[axis] += (1.0 - near / self.wall_margin).max(0.0) - (1.0 - far / self.wall_margin).max(0.0);
+            }
+        }
+
+        (seek(observation) + avoid * self.avoid_weight + walls).normalize_or_zero()
+    }
+}
+
+// towards the nearest star, or nowhere without one
+fn seek(observation: &Observation) -> Vec2 {
+    observation.stars.first().map_or(Vec2::ZERO, |star| (*star - observation.position).normalize_or_zero())
+}
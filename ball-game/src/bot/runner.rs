@@ -0,0 +1,228 @@
+// many rounds played by a bot, headless: `cargo run -p ball-game --release -- --bots --policy greedy`
+//
+// every episode is a round from a fresh game, seeded with the seed of the run plus its number, and
+// played at full speed on the fixed time step until it is over or runs out of ticks. the report is
+// printed as JSON, with how the scores of the episodes are spread, so bots can be compared.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use super::{Bot, Policy, policies};
+use crate::headless::{TICK, enter_game, headless_app};
+use crate::mode::GameMode;
+use crate::player::components::{PLAYERS, Player};
+use crate::replay::resources::ReplayMode;
+use crate::rng::GameRng;
+use crate::score::resources::Score;
+
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+    pub policy: String,
+    pub episodes: u32,
+    pub mode: GameMode,
+    pub seed: u64,
+    // an episode still going after this many ticks is cut short
+    pub max_ticks: u32,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        RunnerConfig {
+            policy: policies::PotentialField::NAME.to_string(),
+            episodes: 100,
+            mode: GameMode::Solo,
+            seed: 0,
+            // ten minutes of play
+            max_ticks: 64 * 60 * 10,
+        }
+    }
+}
+
+impl RunnerConfig {
+    pub const USAGE: &str = "usage: ball-game --bots [--policy greedy|potential] [--episodes N] \
+        [--mode solo|coop|versus] [--seed N] [--max-ticks N]";
+
+    // the arguments after `--bots`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = RunnerConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            let invalid = || format!("invalid value for {arg}: {value}");
+            match arg.as_str() {
+                "--policy" if policies::POLICY_NAMES.contains(&value.as_str()) => config.policy = value,
+                "--policy" => return Err(invalid()),
+                "--episodes" => config.episodes = value.parse().map_err(|_| invalid())?,
+                "--mode" => config.mode = GameMode::from_name(&value).ok_or_else(invalid)?,
+                "--seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "--max-ticks" => config.max_ticks = value.parse().map_err(|_| invalid())?,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// how one round went
+#[derive(Serialize, Debug, Clone)]
+pub struct Episode {
+    pub seed: u64,
+    pub score: u32,
+    // the stars of each player in the round
+    pub players: Vec<u32>,
+    pub ticks: u32,
+    // still going when it ran out of ticks
+    pub timed_out: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RunnerReport {
+    pub policy: String,
+    pub mode: &'static str,
+    pub seed: u64,
+    pub episodes: usize,
+    pub timed_out: usize,
+    pub total_ms: f64,
+    pub score: Distribution,
+    // the stars of each player, when there are more than one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub players: Vec<Distribution>,
+    // how long the players lasted, in seconds
+    pub survival_seconds: Distribution,
+    pub runs: Vec<Episode>,
+}
+
+// how a value is spread over the episodes
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub p10: f64,
+    pub median: f64,
+    pub p90: f64,
+    // how many episodes had each value, for whole values
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub histogram: BTreeMap<u32, usize>,
+}
+
+impl Distribution {
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Self {
+        let mut values: Vec<f64> = values.into_iter().collect();
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count;
+        // nearest rank
+        let percentile = |p: f64| values[((p * count).ceil() as usize).clamp(1, values.len()) - 1];
+        let mut histogram = BTreeMap::new();
+        if values.iter().all(|value| value.fract() == 0.0 && *value >= 0.0) {
+            for value in &values {
+                *histogram.entry(*value as u32).or_default() += 1;
+            }
+        }
+        Distribution {
+            min: values[0],
+            max: values[values.len() - 1],
+            mean,
+            std_dev: variance.sqrt(),
+            p10: percentile(0.1),
+            median: percentile(0.5),
+            p90: percentile(0.9),
+            histogram,
+        }
+    }
+}
+
+// plays the episodes and prints the report to stdout
+pub fn run(config: RunnerConfig) {
+    let started = Instant::now();
+    // every episode starts with a new policy
+    let episodes = play_episodes(&config, || {
+        policies::from_name(&config.policy).expect("the policy is checked when parsing")
+    });
+    let report = report(&config, episodes, started.elapsed().as_secs_f64() * 1000.0);
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(error) => eprintln!("couldn't write the report: {error}"),
+    }
+}
+
+// plays `config.episodes` rounds, each with a policy from `make_policy`
+pub fn play_episodes(config: &RunnerConfig, mut make_policy: impl FnMut() -> Box<dyn Policy>) -> Vec<Episode> {
+    (0..config.episodes)
+        .map(|episode| {
+            let seed = config.seed.wrapping_add(episode as u64);
+            play_episode(seed, config.mode, config.max_ticks, make_policy())
+        })
+        .collect()
+}
+
+// one round from a fresh game, until every player is out or `max_ticks` have gone by
+pub fn play_episode(seed: u64, mode: GameMode, max_ticks: u32, policy: Box<dyn Policy>) -> Episode {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        // exactly one fixed tick per update
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        // the episodes aren't recorded, and keep their seed
+        .insert_resource(ReplayMode::Off)
+        .insert_resource(GameRng::seeded(seed))
+        .insert_resource(mode)
+        .insert_resource(Bot(policy));
+    app.finish();
+    app.cleanup();
+    enter_game(&mut app);
+
+    let mut ticks = 0;
+    while ticks < max_ticks && players_left(app.world_mut()) {
+        app.update();
+        ticks += 1;
+    }
+
+    let timed_out = players_left(app.world_mut());
+    let score = app.world().resource::<Score>();
+    Episode {
+        seed,
+        score: score.value,
+        players: score.players[..mode.players().len()].to_vec(),
+        ticks,
+        timed_out,
+    }
+}
+
+fn players_left(world: &mut World) -> bool {
+    world.query_filtered::<(), With<Player>>().iter(world).next().is_some()
+}
+
+fn report(config: &RunnerConfig, runs: Vec<Episode>, total_ms: f64) -> RunnerReport {
+    let players = if config.mode.players().len() > 1 {
+        (0..PLAYERS)
+            .map(|index| Distribution::of(runs.iter().map(|run| run.players[index] as f64)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut survival_seconds = Distribution::of(runs.iter().map(|run| run.ticks as f64 * TICK.as_secs_f64()));
+    survival_seconds.histogram.clear();
+    RunnerReport {
+        policy: config.policy.clone(),
+        mode: config.mode.name(),
+        seed: config.seed,
+        episodes: runs.len(),
+        timed_out: runs.iter().filter(|run| run.timed_out).count(),
+        total_ms,
+        score: Distribution::of(runs.iter().map(|run| run.score as f64)),
+        players,
+        survival_seconds,
+        runs,
+    }
+}
@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Overlay;
+
+#[derive(Component)]
+pub struct OverlayText;
+
+// the bar of the frame time graph showing this many frames ago
+#[derive(Component)]
+pub struct GraphBar(pub usize);
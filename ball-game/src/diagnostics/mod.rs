@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::STATE;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+// enough frames for a meaningful 0.1% low
+pub const HISTORY_FRAMES: usize = 1000;
+// bars in the frame time graph, one per frame
+pub const GRAPH_BARS: usize = 120;
+// frame time at the top of the graph, in milliseconds
+pub const GRAPH_MAX_MS: f32 = 33.3;
+// frames slower than this are drawn red in the graph
+pub const SLOW_FRAME_MS: f32 = 16.7;
+// length of a CSV capture
+pub const CAPTURE_SECONDS: f32 = 10.0;
+// captures are written here, next to where the game is run
+pub const CAPTURE_FOLDER: &str = "captures";
+
+pub struct FrameOverlayPlugin;
+
+impl Plugin for FrameOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameHistory>()
+            .init_resource::<FixedTicks>()
+            .init_resource::<Capture>()
+            .add_systems(OnEnter(STATE), (reset_diagnostics, spawn_overlay))
+            .add_systems(FixedFirst, count_fixed_tick.run_if(in_state(STATE)))
+            .add_systems(Update, (
+                record_frame,
+                toggle_overlay,
+                start_capture,
+                record_capture,
+                update_overlay_text,
+                update_graph,
+            ).chain().run_if(in_state(STATE)));
+    }
+}
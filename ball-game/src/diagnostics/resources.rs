@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::HISTORY_FRAMES;
+
+// frame times of the last frames in milliseconds, newest last
+#[derive(Resource, Debug, Default)]
+pub struct FrameHistory {
+    pub frame_times: VecDeque<f32>,
+}
+
+impl FrameHistory {
+    pub fn push(&mut self, frame_ms: f32) {
+        if self.frame_times.len() == HISTORY_FRAMES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_ms);
+    }
+
+    pub fn average_fps(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        let average = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+        1000.0 / average
+    }
+
+    // average fps over the slowest `fraction` of the frames, e.g. 0.01 for the 1% low
+    pub fn low_fps(&self, fraction: f32) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        let mut slowest: Vec<f32> = self.frame_times.iter().copied().collect();
+        slowest.sort_by(|a, b| b.total_cmp(a));
+        let count = ((slowest.len() as f32 * fraction).ceil() as usize).max(1);
+        let average = slowest[..count].iter().sum::<f32>() / count as f32;
+        1000.0 / average
+    }
+}
+
+// fixed timestep ticks run this frame, and frames which needed more than one to catch up
+#[derive(Resource, Debug, Default)]
+pub struct FixedTicks {
+    pub this_frame: u32,
+    pub last_frame: u32,
+    pub overruns: u32,
+    pub most_in_a_frame: u32,
+}
+
+// one line of a CSV capture
+#[derive(Debug, Clone)]
+pub struct CaptureRow {
+    pub frame: u32,
+    pub time: f32,
+    pub frame_ms: f32,
+    pub enemies: usize,
+    pub stars: usize,
+    pub fixed_ticks: u32,
+}
+
+// frames recorded for a CSV export, while a capture is running
+#[derive(Resource, Debug, Default)]
+pub struct Capture {
+    pub timer: Option<Timer>,
+    pub rows: Vec<CaptureRow>,
+}
@@ -0,0 +1,252 @@
+use action_map::{ActionMap, ActionState};
+use bevy::prelude::*;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::components::*;
+use super::resources::*;
+use super::{CAPTURE_FOLDER, CAPTURE_SECONDS, GRAPH_BARS, GRAPH_MAX_MS, SLOW_FRAME_MS};
+use crate::actions::GameAction;
+use crate::enemy::components::Enemy;
+use crate::framerate::FramePacingStats;
+use crate::star::components::Star;
+use crate::STATE;
+
+const OVERLAY_FONT_SIZE: f32 = 14.0;
+const GRAPH_HEIGHT: f32 = 60.0;
+const BAR_WIDTH: f32 = 2.0;
+const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+const FAST_BAR_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+const SLOW_BAR_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+pub fn reset_diagnostics(
+    mut history: ResMut<FrameHistory>,
+    mut fixed_ticks: ResMut<FixedTicks>,
+    mut capture: ResMut<Capture>,
+) {
+    *history = FrameHistory::default();
+    *fixed_ticks = FixedTicks::default();
+    *capture = Capture::default();
+}
+
+// hidden until toggled, in the top left corner
+pub fn spawn_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Overlay,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            BackgroundColor(OVERLAY_BACKGROUND),
+            Visibility::Hidden,
+            GlobalZIndex(10),
+            StateScoped(STATE),
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                OverlayText,
+                Text::default(),
+                TextFont::from_font_size(OVERLAY_FONT_SIZE),
+            ));
+            overlay
+                .spawn(Node {
+                    height: Val::Px(GRAPH_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                })
+                .with_children(|graph| {
+                    // oldest frame on the left
+                    for frames_ago in (0..GRAPH_BARS).rev() {
+                        graph.spawn((
+                            GraphBar(frames_ago),
+                            Node {
+                                width: Val::Px(BAR_WIDTH),
+                                height: Val::Px(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(FAST_BAR_COLOR),
+                        ));
+                    }
+                });
+        });
+}
+
+pub fn count_fixed_tick(mut fixed_ticks: ResMut<FixedTicks>) {
+    fixed_ticks.this_frame += 1;
+}
+
+// the fixed ticks of a frame run before its Update
+pub fn record_frame(
+    time: Res<Time<Real>>,
+    mut history: ResMut<FrameHistory>,
+    mut fixed_ticks: ResMut<FixedTicks>,
+) {
+    history.push(time.delta_secs() * 1000.0);
+
+    let ticks = fixed_ticks.this_frame;
+    if ticks > 1 {
+        fixed_ticks.overruns += 1;
+    }
+    fixed_ticks.most_in_a_frame = fixed_ticks.most_in_a_frame.max(ticks);
+    fixed_ticks.last_frame = ticks;
+    fixed_ticks.this_frame = 0;
+}
+
+pub fn toggle_overlay(
+    actions: Res<ActionState<GameAction>>,
+    mut overlay: Single<&mut Visibility, With<Overlay>>,
+) {
+    if actions.just_pressed(GameAction::ToggleOverlay) {
+        overlay.toggle_visible_hidden();
+    }
+}
+
+pub fn start_capture(actions: Res<ActionState<GameAction>>, mut capture: ResMut<Capture>) {
+    if actions.just_pressed(GameAction::CaptureFrames) && capture.timer.is_none() {
+        info!("Capturing frame times for {} seconds", CAPTURE_SECONDS);
+        capture.timer = Some(Timer::from_seconds(CAPTURE_SECONDS, TimerMode::Once));
+        capture.rows.clear();
+    }
+}
+
+pub fn record_capture(
+    time: Res<Time<Real>>,
+    mut capture: ResMut<Capture>,
+    history: Res<FrameHistory>,
+    fixed_ticks: Res<FixedTicks>,
+    enemies: Query<(), With<Enemy>>,
+    stars: Query<(), With<Star>>,
+) {
+    let capture = &mut *capture;
+    let Some(timer) = &mut capture.timer else {
+        return;
+    };
+    timer.tick(time.delta());
+
+    capture.rows.push(CaptureRow {
+        frame: capture.rows.len() as u32,
+        time: timer.elapsed_secs(),
+        frame_ms: history.frame_times.back().copied().unwrap_or_default(),
+        enemies: enemies.iter().count(),
+        stars: stars.iter().count(),
+        fixed_ticks: fixed_ticks.last_frame,
+    });
+
+    if timer.finished() {
+        capture.timer = None;
+        match write_capture(&capture.rows) {
+            Ok(path) => info!("Wrote {} frames to {}", capture.rows.len(), path.display()),
+            Err(error) => warn!("Couldn't write the frame capture: {error}"),
+        }
+    }
+}
+
+fn write_capture(rows: &[CaptureRow]) -> io::Result<PathBuf> {
+    fs::create_dir_all(CAPTURE_FOLDER)?;
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    let path = PathBuf::from(CAPTURE_FOLDER).join(format!("frames-{seconds}.csv"));
+
+    let mut file = io::BufWriter::new(fs::File::create(&path)?);
+    writeln!(file, "frame,time_s,frame_ms,fps,enemies,stars,fixed_ticks")?;
+    for row in rows {
+        let fps = if row.frame_ms > 0.0 { 1000.0 / row.frame_ms } else { 0.0 };
+        writeln!(
+            file,
+            "{},{:.4},{:.3},{:.1},{},{},{}",
+            row.frame, row.time, row.frame_ms, fps, row.enemies, row.stars, row.fixed_ticks
+        )?;
+    }
+    file.flush()?;
+    Ok(path)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_overlay_text(
+    overlay: Single<&Visibility, With<Overlay>>,
+    mut text: Single<&mut Text, With<OverlayText>>,
+    history: Res<FrameHistory>,
+    fixed_ticks: Res<FixedTicks>,
+    capture: Res<Capture>,
+    pacing: Option<Res<FramePacingStats>>,
+    action_map: Res<ActionMap<GameAction>>,
+    enemies: Query<(), With<Enemy>>,
+    stars: Query<(), With<Star>>,
+) {
+    if **overlay == Visibility::Hidden {
+        return;
+    }
+
+    let mut lines = vec![
+        format!(
+            "FPS {:.0}   1% low {:.0}   0.1% low {:.0}",
+            history.average_fps(),
+            history.low_fps(0.01),
+            history.low_fps(0.001),
+        ),
+        format!(
+            "frame {:.1} ms",
+            history.frame_times.back().copied().unwrap_or_default()
+        ),
+        format!(
+            "enemies {}   stars {}",
+            enemies.iter().count(),
+            stars.iter().count()
+        ),
+        format!(
+            "fixed ticks {}   overruns {}   most in a frame {}",
+            fixed_ticks.last_frame, fixed_ticks.overruns, fixed_ticks.most_in_a_frame
+        ),
+    ];
+    if let Some(pacing) = pacing {
+        let target = match pacing.target {
+            Some(target) => format!("{:.0} fps", 1.0 / target.as_secs_f64()),
+            None => "off".to_string(),
+        };
+        lines.push(format!(
+            "pacing {}   idle {:.0}%   late frames {}",
+            target,
+            pacing.idle_ratio() * 100.0,
+            pacing.late_frames
+        ));
+    }
+    lines.push(match &capture.timer {
+        Some(timer) => format!("capturing {:.1} s", timer.remaining_secs()),
+        None => match action_map.bindings(GameAction::CaptureFrames).first() {
+            Some(binding) => format!("{binding}: capture {CAPTURE_SECONDS} s to CSV"),
+            None => "capture unbound".to_string(),
+        },
+    });
+
+    text.0 = lines.join("\n");
+}
+
+pub fn update_graph(
+    overlay: Single<&Visibility, With<Overlay>>,
+    history: Res<FrameHistory>,
+    mut bars: Query<(&GraphBar, &mut Node, &mut BackgroundColor)>,
+) {
+    if **overlay == Visibility::Hidden {
+        return;
+    }
+
+    let frames = history.frame_times.len();
+    for (bar, mut node, mut color) in &mut bars {
+        let frame_ms = match frames.checked_sub(bar.0 + 1) {
+            Some(index) => history.frame_times[index],
+            None => 0.0,
+        };
+        node.height = Val::Px((frame_ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT);
+        color.0 = if frame_ms > SLOW_FRAME_MS { SLOW_BAR_COLOR } else { FAST_BAR_COLOR };
+    }
+}
@@ -1,11 +1,14 @@
 use bevy::prelude::*;
+use common::{AddSoundExt, Sound};
 
+use crate::{BallGameSet, STATE};
 use crate::star::STAR_SIZE;
 
 pub mod components;
 pub mod resources;
 mod systems;
 
+use components::Enemy;
 use resources::*;
 use systems::*;
 
@@ -13,18 +16,27 @@ pub const NUMBER_OF_ENEMIES: usize = 4;
 pub const ENEMY_SPEED: f32 = 200.0;
 pub const ENEMY_SIZE: f32 = 64.0; // This is the enemy sprite size.
 pub const SAFE_DISTANCE: f32 = ENEMY_SIZE + STAR_SIZE + 20.0;  // e.g. leave a 20px margin
+pub const BOUNCE_SOUND: &str = "ball-game/bounce";
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EnemySpawnTimer>()
-            .add_systems(Startup, spawn_enemies)
-            .add_systems(Update, enemy_movement)
-            .add_systems(Update, update_enemy_direction)
-            .add_systems(Update, confine_enemy_movement)
-            .add_systems(Update, enemy_hit_star)
-            .add_systems(Update, tick_enemy_spawn_timer)
-            .add_systems(Update, spawn_enemies_over_time);
+            .register_type::<Enemy>()
+            .register_type::<EnemySpawnTimer>()
+            // many enemies bounce at once, keep it to a few plucks
+            .add_sound(BOUNCE_SOUND, Sound::sfx(&["audio/pluck_001.ogg", "audio/pluck_002.ogg"])
+                .max_playing(3)
+                .cooldown(0.05))
+            .add_systems(OnEnter(STATE), (reset_enemy_spawn_timer, spawn_enemies).in_set(BallGameSet::Enemy))
+            .add_systems(FixedUpdate, (
+                enemy_movement,
+                update_enemy_direction,
+                confine_enemy_movement,
+                enemy_hit_star,
+                tick_enemy_spawn_timer,
+                spawn_enemies_over_time,
+            ).chain().in_set(BallGameSet::Enemy));
     }
 }
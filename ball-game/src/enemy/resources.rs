@@ -2,7 +2,8 @@ use bevy::prelude::*;
 
 pub const ENEMY_SPAWN_TIME: f32 = 5.0;
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
 pub struct EnemySpawnTimer {
     pub timer: Timer,
 }
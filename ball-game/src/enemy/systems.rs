@@ -1,30 +1,36 @@
 use bevy::prelude::*;
 use bevy::window::{ Window,  PrimaryWindow};
-use rand::random;
+use rand::Rng;
+use common::PlaySoundExt;
 
 use super::components::*;
 use super::resources::*;
-use super::{ENEMY_SIZE, ENEMY_SPEED, NUMBER_OF_ENEMIES};
+use super::{BOUNCE_SOUND, ENEMY_SIZE, ENEMY_SPEED, NUMBER_OF_ENEMIES};
 
+use crate::STATE;
+use crate::assets::BallGameAssets;
+use crate::rng::GameRng;
 use crate::star::{STAR_SIZE, components::Star};
 
 
 pub fn spawn_enemies(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    assets: Res<BallGameAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     let window = window_query.single().unwrap();
 
     for _ in 0..NUMBER_OF_ENEMIES {
-        let random_x = random::<f32>() * window.width();
-        let random_y = random::<f32>() * window.height();
+        let random_x = rng.random::<f32>() * window.width();
+        let random_y = rng.random::<f32>() * window.height();
 
         commands.spawn((
             //Create s sprite for the enemies
-            Sprite::from_image(asset_server.load("sprites/ball_red_large.png")),
+            Sprite::from_image(assets.enemy.clone()),
             Transform::from_xyz(random_x, random_y, 0.0),
-            Enemy {direction: Vec2::new(random::<f32>(), random::<f32>()).normalize()},
+            Enemy {direction: Vec2::new(rng.random::<f32>(), rng.random::<f32>()).normalize()},
+            StateScoped(STATE),
         ));
     }
 }
@@ -40,7 +46,6 @@ pub fn update_enemy_direction(
     mut commands: Commands,
     mut enemy_query: Query<(&Transform, &mut Enemy)>,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
 ) {
     let window = window_query.single().unwrap();
 
@@ -65,17 +70,8 @@ pub fn update_enemy_direction(
 
         // Play SFX
         if direction_changed {
-            // Play Sound Effect
-            // Load two variants and choose one at random
-            let s1 = asset_server.load("audio/pluck_001.ogg");
-            let s2 = asset_server.load("audio/pluck_002.ogg");
-            let pick = if rand::random::<f32>() > 0.5 { s1 } else { s2 };
-
-            // Spawn an entity to play the sound once
-            commands.spawn((
-                AudioPlayer::new(pick),
-                PlaybackSettings::ONCE,
-            ));
+            // Play Sound Effect, one of two variants at random
+            commands.play_sound(BOUNCE_SOUND);
         }
     }
 }
@@ -114,6 +110,7 @@ pub fn confine_enemy_movement(
 
 
 
+#[allow(clippy::type_complexity)]
 pub fn enemy_hit_star(
     star_query: Query<&Transform, (With<Star>, Without<Enemy>)>,
     mut enemy_query: Query< (&Transform, &mut Enemy), (With<Enemy>, Without<Star>)>,
@@ -149,6 +146,10 @@ pub fn enemy_hit_star(
     }
 }
 
+pub fn reset_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>) {
+    *enemy_spawn_timer = EnemySpawnTimer::default();
+}
+
 pub fn tick_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>, time: Res<Time>) {
     enemy_spawn_timer.timer.tick(time.delta());
 }
@@ -156,20 +157,22 @@ pub fn tick_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>, ti
 pub fn spawn_enemies_over_time(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    assets: Res<BallGameAssets>,
+    mut rng: ResMut<GameRng>,
     enemy_spawn_timer: Res<EnemySpawnTimer>,
 ) {
     if enemy_spawn_timer.timer.finished() {
         let window = window_query.single().unwrap();
 
-        let random_x = random::<f32>() * window.width();
-        let random_y = random::<f32>() * window.height();
+        let random_x = rng.random::<f32>() * window.width();
+        let random_y = rng.random::<f32>() * window.height();
 
         commands.spawn((
             //Create s sprite for the enemies
-            Sprite::from_image(asset_server.load("sprites/ball_red_large.png")),
+            Sprite::from_image(assets.enemy.clone()),
             Transform::from_xyz(random_x, random_y, 0.0),
-            Enemy {direction: Vec2::new(random::<f32>(), random::<f32>()).normalize()},
+            Enemy {direction: Vec2::new(rng.random::<f32>(), rng.random::<f32>()).normalize()},
+            StateScoped(STATE),
         ));
 
     }
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
+use crate::player::components::PlayerId;
+
 #[derive(Event)]
 pub struct GameOver {
+    // the shared score, or the winner's in versus
     pub score: u32,
+    // who won a versus round, none for a draw or when playing together
+    pub winner: Option<PlayerId>,
 }
@@ -1,94 +1,325 @@
 use bevy::prelude::*;
 use bevy::app::Last;
-use spin_sleep::sleep;
-use std::sync::atomic::{AtomicU64, Ordering};
+use bevy::window::{Monitor, PrimaryMonitor, PrimaryWindow};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
-/// Holds timing and pacing state for frame limiting.
-#[derive(Resource)]
-pub struct FrameLimiterState {
-    /// Reference start time for high-precision elapsed.
+/// Refresh rate assumed when the monitor doesn't report one.
+const FALLBACK_REFRESH_RATE: f64 = 60.0;
+/// Number of frames the rolling statistics are computed over.
+const STATS_WINDOW: usize = 120;
+
+/// How the frame rate is limited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PacingMode {
+    /// Don't limit the frame rate.
+    Off,
+    /// Cap the frame rate at this many frames per second.
+    Fixed(f64),
+    /// Cap the frame rate at the refresh rate of the primary monitor.
+    MatchRefresh,
+    /// Don't limit the frame rate while the window is focused, and cap it at this many frames
+    /// per second when it isn't, to save power in the background.
+    LowPower(f64),
+}
+
+/// The frame pacing settings, which can be changed at any time.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct FramePacing {
+    pub mode: PacingMode,
+}
+
+impl FramePacing {
+    /// Time between two frames, or `None` when the frame rate isn't limited.
+    fn interval(&self, refresh_rate: Option<f64>, focused: bool) -> Option<Duration> {
+        let fps = match self.mode {
+            PacingMode::Off => return None,
+            PacingMode::Fixed(fps) => fps,
+            PacingMode::MatchRefresh => refresh_rate.unwrap_or(FALLBACK_REFRESH_RATE),
+            PacingMode::LowPower(_) if focused => return None,
+            PacingMode::LowPower(fps) => fps,
+        };
+        Some(Duration::from_secs_f64(1.0 / fps.max(1.0)))
+    }
+}
+
+/// Rolling statistics over the last frames, instead of logging every frame.
+#[derive(Resource, Debug, Default)]
+pub struct FramePacingStats {
+    /// Time between the starts of the last frames, oldest first.
+    pub frame_times: VecDeque<Duration>,
+    /// Time slept by the limiter in the last frames, oldest first.
+    pub sleep_times: VecDeque<Duration>,
+    /// Frames which started after their deadline, since the pacing settings last changed.
+    pub late_frames: u64,
+    /// Time between two frames currently aimed for.
+    pub target: Option<Duration>,
+}
+
+impl FramePacingStats {
+    fn record(&mut self, frame_time: Duration, slept: Duration) {
+        if self.frame_times.len() == STATS_WINDOW {
+            self.frame_times.pop_front();
+            self.sleep_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.sleep_times.push_back(slept);
+    }
+
+    /// Average frame time over the last frames.
+    pub fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::ZERO;
+        }
+        self.frame_times.iter().sum::<Duration>() / self.frame_times.len() as u32
+    }
+
+    /// Longest frame time over the last frames.
+    pub fn worst_frame_time(&self) -> Duration {
+        self.frame_times.iter().max().copied().unwrap_or_default()
+    }
+
+    /// Average frames per second over the last frames.
+    pub fn fps(&self) -> f64 {
+        let average = self.average_frame_time().as_secs_f64();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+
+    /// Part of the frame time spent sleeping over the last frames, from 0 to 1.
+    pub fn idle_ratio(&self) -> f64 {
+        let total = self.frame_times.iter().sum::<Duration>().as_secs_f64();
+        if total > 0.0 {
+            self.sleep_times.iter().sum::<Duration>().as_secs_f64() / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Where the limiter reads the time and how it waits, so pacing can be checked without
+/// real sleeps.
+pub trait Clock: Send + Sync + 'static {
+    /// Time since some fixed point.
+    fn now(&self) -> Duration;
+    /// Wait for `duration`.
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real clock, sleeping with spin_sleep's hybrid OS sleep and spin for precision.
+pub struct SystemClock {
     init: Instant,
-    /// Desired frame interval in nanoseconds (1e9 / FPS).
-    target_ns: AtomicU64,
-    /// "Memory target": timestamp (ns since `init`) when the next frame should start.
-    memory_target_ns: AtomicU64,
-    /// Timestamp (ns since `init`) when the previous frame began.
-    last_frame_ns: AtomicU64,
 }
 
-/// A Bevy plugin that caps the frame rate by sleeping the main thread.
-pub struct FrameLimiterPlugin {
-    /// Desired frames per second.
-    pub target_fps: f64,
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self { init: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.init.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        spin_sleep::sleep(duration);
+    }
+}
+
+/// Sleeps at the end of each frame until the next frame is due.
+#[derive(Resource)]
+pub struct FramePacer {
+    clock: Box<dyn Clock>,
+    /// When the current frame started, after sleeping.
+    frame_start: Option<Duration>,
+    /// When the next frame should start.
+    deadline: Option<Duration>,
+}
+
+impl FramePacer {
+    pub fn new(clock: impl Clock) -> Self {
+        Self {
+            clock: Box::new(clock),
+            frame_start: None,
+            deadline: None,
+        }
+    }
+
+    /// Forget the deadline, so a new target doesn't have to catch up with the old one.
+    fn reset(&mut self) {
+        self.deadline = None;
+    }
+
+    /// End the current frame: sleep until the next one is due with `interval` between frames,
+    /// and record it in `stats`.
+    fn wait(&mut self, interval: Option<Duration>, stats: &mut FramePacingStats) {
+        let now = self.clock.now();
+        let mut slept = Duration::ZERO;
+
+        match interval {
+            Some(interval) => {
+                let deadline = self.deadline.unwrap_or(now);
+                if now < deadline {
+                    slept = deadline - now;
+                    self.clock.sleep(slept);
+                } else if self.deadline.is_some() && now > deadline {
+                    stats.late_frames += 1;
+                }
+                // Aim for even spacing, but don't rush frames to make up for a long one.
+                let next = deadline + interval;
+                let start = now + slept;
+                self.deadline = Some(if next < start { start + interval } else { next });
+            }
+            None => self.deadline = None,
+        }
+
+        let start = self.clock.now();
+        if let Some(previous) = self.frame_start.replace(start) {
+            stats.record(start - previous, slept);
+        }
+        stats.target = interval;
+    }
+}
+
+/// A Bevy plugin pacing frames by sleeping the main thread at the end of each frame.
+///
+/// Change the [`FramePacing`] resource to change the mode at runtime, and read
+/// [`FramePacingStats`] to see how it does.
+pub struct FramePacingPlugin {
+    pub mode: PacingMode,
 }
 
-impl Default for FrameLimiterPlugin {
+impl Default for FramePacingPlugin {
     fn default() -> Self {
-        Self { target_fps: 60.0 }
+        Self { mode: PacingMode::Fixed(60.0) }
     }
 }
 
-impl FrameLimiterPlugin {
-    /// Create the plugin with a custom FPS cap.
-    pub fn with_fps(fps: f64) -> Self {
-        Self { target_fps: fps }
+impl FramePacingPlugin {
+    /// Create the plugin with a custom mode.
+    pub fn new(mode: PacingMode) -> Self {
+        Self { mode }
     }
 }
 
-impl Plugin for FrameLimiterPlugin {
+impl Plugin for FramePacingPlugin {
     fn build(&self, app: &mut App) {
-        // Compute nanoseconds per frame from FPS.
-        let fps = self.target_fps.max(1.0);
-        let target_ns = (1_000_000_000.0 / fps) as u64;
-
-        // Mark "now" as the zero point.
-        let now = Instant::now();
-        // First memory target = one interval after start.
-        let first_target = target_ns;
-
-        app.insert_resource(FrameLimiterState {
-            init: now,
-            target_ns: AtomicU64::new(target_ns),
-            memory_target_ns: AtomicU64::new(first_target),
-            last_frame_ns: AtomicU64::new(0),
-        });
-
-        // Add the frame limiter system to run at the end of each frame.
-        app.add_systems(Last, (enforce_frame_rate,));
-    }
-}
-
-/// Enforces the frame cap by sleeping the thread and logs instantaneous FPS.
-fn enforce_frame_rate(state: Res<FrameLimiterState>) {
-    // 1) Absolute current time (ns since init).
-    let now_ns = state.init.elapsed().as_nanos() as u64;
-    
-    // 2) Instantaneous FPS calculation.
-    let prev_ns = state.last_frame_ns.swap(now_ns, Ordering::Relaxed);
-    if prev_ns != 0 {
-        let dt_ns = now_ns.saturating_sub(prev_ns);
-        let fps = 1_000_000_000.0 / (dt_ns as f64);
-        info!("Instant FPS: {:.2}", fps);
-    }
-
-    // 3) Frame pacing: compute error vs memory target.
-    let target_ns = state.target_ns.load(Ordering::Relaxed);
-    let mem_t_ns = state.memory_target_ns.load(Ordering::Relaxed);
-    let error_ns_i = now_ns as i128 - mem_t_ns as i128;
-
-    // How long to wait = target_ns - error_ns_i (clamped ≥ 0).
-    let wait_ns = (target_ns as i128 - error_ns_i).max(0) as u64;
-    
-    // Log error and wait time if needed.
-    info!("Frame Error: {:+} ns | wait: {} ns", error_ns_i, wait_ns);
-
-    // 4) Sleep the remaining time using spin_sleep's hybrid OS+spin.
-    if wait_ns > 0 {
-        sleep(Duration::from_nanos(wait_ns));
-    }
-
-    // 5) Schedule next memory target based on *actual* now timestamp.
-    let next_mem = now_ns.saturating_add(target_ns);
-    state.memory_target_ns.store(next_mem, Ordering::Relaxed);
+        app.insert_resource(FramePacing { mode: self.mode })
+            .init_resource::<FramePacingStats>()
+            .insert_resource(FramePacer::new(SystemClock::default()))
+            // Run last, so the sleep happens after everything else in the frame.
+            .add_systems(Last, pace_frame);
+    }
+}
+
+/// Sleeps until the next frame is due, following the current [`FramePacing`].
+fn pace_frame(
+    pacing: Res<FramePacing>,
+    mut pacer: ResMut<FramePacer>,
+    mut stats: ResMut<FramePacingStats>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+    monitor: Option<Single<&Monitor, With<PrimaryMonitor>>>,
+) {
+    if pacing.is_changed() {
+        info!("Frame pacing: {:?}", pacing.mode);
+        pacer.reset();
+        stats.late_frames = 0;
+    }
+
+    let refresh_rate = monitor
+        .and_then(|monitor| monitor.refresh_rate_millihertz)
+        .map(|millihertz| millihertz as f64 / 1000.0);
+    let focused = window.is_none_or(|window| window.focused);
+    let interval = pacing.interval(refresh_rate, focused);
+    pacer.wait(interval, &mut stats);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A clock which only moves when slept on or advanced by the test.
+    #[derive(Clone, Default)]
+    struct MockClock(Arc<Mutex<Duration>>);
+
+    impl MockClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Duration {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.advance(duration);
+        }
+    }
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn frames_are_evenly_spaced() {
+        let clock = MockClock::default();
+        let mut pacer = FramePacer::new(clock.clone());
+        let mut stats = FramePacingStats::default();
+
+        pacer.wait(Some(INTERVAL), &mut stats);
+        for work in [1, 3, 9, 0, 5] {
+            clock.advance(Duration::from_millis(work));
+            pacer.wait(Some(INTERVAL), &mut stats);
+        }
+
+        assert_eq!(clock.now(), 5 * INTERVAL);
+        assert!(stats.frame_times.iter().all(|&frame_time| frame_time == INTERVAL));
+        assert_eq!(stats.late_frames, 0);
+        assert_eq!(stats.fps().round(), 100.0);
+    }
+
+    #[test]
+    fn long_frames_are_not_made_up_for() {
+        let clock = MockClock::default();
+        let mut pacer = FramePacer::new(clock.clone());
+        let mut stats = FramePacingStats::default();
+
+        pacer.wait(Some(INTERVAL), &mut stats);
+        clock.advance(Duration::from_millis(35));
+        pacer.wait(Some(INTERVAL), &mut stats);
+        clock.advance(Duration::from_millis(2));
+        pacer.wait(Some(INTERVAL), &mut stats);
+
+        assert_eq!(stats.late_frames, 1);
+        assert_eq!(
+            stats.frame_times,
+            [Duration::from_millis(35), INTERVAL]
+        );
+    }
+
+    #[test]
+    fn off_never_sleeps() {
+        let clock = MockClock::default();
+        let mut pacer = FramePacer::new(clock.clone());
+        let mut stats = FramePacingStats::default();
+
+        for _ in 0..3 {
+            clock.advance(Duration::from_millis(1));
+            pacer.wait(None, &mut stats);
+        }
+
+        assert_eq!(clock.now(), Duration::from_millis(3));
+        assert_eq!(stats.idle_ratio(), 0.0);
+    }
+
+    #[test]
+    fn low_power_only_limits_when_unfocused() {
+        let pacing = FramePacing { mode: PacingMode::LowPower(10.0) };
+        assert_eq!(pacing.interval(None, true), None);
+        assert_eq!(pacing.interval(None, false), Some(Duration::from_millis(100)));
+
+        let pacing = FramePacing { mode: PacingMode::MatchRefresh };
+        assert_eq!(pacing.interval(Some(50.0), true), Some(Duration::from_millis(20)));
+    }
 }
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+// the player of the best run, following its trajectory
+#[derive(Component)]
+pub struct Ghost {
+    pub trajectory: Vec<Vec2>,
+    // fixed ticks followed so far
+    pub tick: usize,
+}
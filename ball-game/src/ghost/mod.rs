@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use crate::{BallGameSet, STATE};
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+// the ghost is a faint copy of the player
+pub const GHOST_ALPHA: f32 = 0.35;
+// drawn behind the player and the enemies
+pub const GHOST_DEPTH: f32 = -1.0;
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trajectory>()
+            .add_systems(OnEnter(STATE), (reset_trajectory, spawn_ghost).in_set(BallGameSet::Player))
+            .add_systems(FixedUpdate, (
+                record_trajectory,
+                move_ghost,
+            ).chain().in_set(BallGameSet::Ghost));
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+// where the player has been in this run, after every fixed tick
+#[derive(Resource, Debug, Default)]
+pub struct Trajectory {
+    pub positions: Vec<Vec2>,
+}
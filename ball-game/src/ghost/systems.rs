@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use super::components::Ghost;
+use super::resources::Trajectory;
+use super::{GHOST_ALPHA, GHOST_DEPTH};
+
+use crate::STATE;
+use crate::assets::BallGameAssets;
+use crate::mode::GameMode;
+use crate::player::components::Player;
+use crate::score::resources::HighScores;
+
+pub fn reset_trajectory(mut trajectory: ResMut<Trajectory>) {
+    trajectory.positions.clear();
+}
+
+// race the best run alone, if there is one yet
+pub fn spawn_ghost(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    assets: Res<BallGameAssets>,
+    mode: Res<GameMode>,
+) {
+    if *mode != GameMode::Solo {
+        return;
+    }
+    let Some(best_run) = &high_scores.best_run else {
+        return;
+    };
+    let Some(&start) = best_run.trajectory.first() else {
+        return;
+    };
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 1.0, 1.0, GHOST_ALPHA),
+            ..Sprite::from_image(assets.player.clone())
+        },
+        Transform::from_translation(start.extend(GHOST_DEPTH)),
+        Ghost {
+            trajectory: best_run.trajectory.clone(),
+            tick: 0,
+        },
+        StateScoped(STATE),
+    ));
+}
+
+pub fn record_trajectory(
+    mut trajectory: ResMut<Trajectory>,
+    player_query: Query<&Transform, With<Player>>,
+    mode: Res<GameMode>,
+) {
+    if *mode == GameMode::Solo && let Ok(transform) = player_query.single() {
+        trajectory.positions.push(transform.translation.truncate());
+    }
+}
+
+// one step of the best run per fixed tick, so the ghost keeps pace with the player
+pub fn move_ghost(
+    mut commands: Commands,
+    mut ghost_query: Query<(Entity, &mut Transform, &mut Ghost)>,
+) {
+    for (entity, mut transform, mut ghost) in ghost_query.iter_mut() {
+        match ghost.trajectory.get(ghost.tick) {
+            Some(position) => transform.translation = position.extend(GHOST_DEPTH),
+            // the best run ended here
+            None => commands.entity(entity).despawn(),
+        }
+        ghost.tick += 1;
+    }
+}
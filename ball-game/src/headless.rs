@@ -0,0 +1,74 @@
+// the game without a window, rendering or audio, for the benchmark, the network server and the
+// tests. the assets are missing without the asset loaders, and replaced by placeholders.
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioSource;
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::window::{PrimaryWindow, WindowResolution};
+use std::time::{Duration, Instant};
+
+use crate::score::resources::HighScoresFile;
+use crate::{BallGamePlugin, LOADING, STATE};
+use common::assets::ASSET_FOLDER;
+use common::{AddGameExt, AppState};
+
+// same window size as the game
+pub const WINDOW_SIZE: f32 = 800.0;
+// how often `App::run` updates, once per default fixed tick
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / 64);
+// the missing assets fail fast, loading shouldn't take longer than this
+const LOADING_TIMEOUT: Duration = Duration::from_secs(10);
+
+// the game in its loading state, still to be finished with `App::finish` and `App::cleanup`
+// so more plugins and resources can be added
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(TICK)),
+        StatesPlugin,
+        AssetPlugin {
+            file_path: ASSET_FOLDER.to_string(),
+            ..default()
+        },
+        InputPlugin,
+    ))
+    .init_asset::<Image>()
+    .init_asset::<AudioSource>()
+    .insert_state(LOADING)
+    .add_game(BallGamePlugin)
+    // runs without anyone playing leave the high scores on disk alone
+    .insert_resource(HighScoresFile(None));
+
+    // the systems of the game read the window size
+    app.world_mut().spawn((
+        Window {
+            resolution: WindowResolution::new(WINDOW_SIZE, WINDOW_SIZE),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    // a single thread keeps the order of the random draws the same between runs
+    app.edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    app.edit_schedule(Update, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    app
+}
+
+// update the finished app until its assets are loaded and the round has started
+pub fn enter_game(app: &mut App) {
+    let loading_started = Instant::now();
+    while *app.world().resource::<State<AppState>>() != STATE {
+        if loading_started.elapsed() > LOADING_TIMEOUT {
+            panic!("ball-game didn't finish loading within {LOADING_TIMEOUT:?}");
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
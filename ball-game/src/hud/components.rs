@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+use crate::player::components::PlayerId;
+
+// holds the texts of the hud
+#[derive(Component)]
+pub struct HudRoot;
+
+// the stars and lives of one player
+#[derive(Component)]
+pub struct PlayerHud(pub PlayerId);
+
+// the shared score in co-op, who leads in versus
+#[derive(Component)]
+pub struct TeamHud;
@@ -0,0 +1,26 @@
+// the stars and lives of every player, along the bottom of the window
+
+use bevy::prelude::*;
+
+use crate::mode::GameMode;
+use crate::{BallGameSet, STATE};
+
+pub mod components;
+mod systems;
+
+use systems::*;
+
+pub const HUD_FONT_SIZE: f32 = 20.0;
+pub const HUD_PADDING: Val = Val::Px(8.0);
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(STATE), spawn_hud.after(BallGameSet::Player))
+            .add_systems(Update, (
+                spawn_hud.run_if(resource_changed::<GameMode>),
+                update_hud,
+            ).chain().run_if(in_state(STATE)));
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use super::components::{HudRoot, PlayerHud, TeamHud};
+use super::{HUD_FONT_SIZE, HUD_PADDING};
+
+use crate::STATE;
+use crate::mode::GameMode;
+use crate::player::PLAYER_COLORS;
+use crate::player::components::{Player, PlayerId};
+use crate::score::resources::Score;
+
+// a continued or imported round may change the mode, and the players shown with it
+pub fn spawn_hud(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    root_query: Query<Entity, With<HudRoot>>,
+) {
+    for root in root_query.iter() {
+        commands.entity(root).despawn();
+    }
+    let font = TextFont::from_font_size(HUD_FONT_SIZE);
+
+    commands
+        .spawn((
+            HudRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: HUD_PADDING,
+                left: HUD_PADDING,
+                right: HUD_PADDING,
+                justify_content: JustifyContent::SpaceBetween,
+                ..default()
+            },
+            StateScoped(STATE),
+        ))
+        .with_children(|parent| {
+            for &id in mode.players() {
+                parent.spawn((
+                    PlayerHud(id),
+                    Text::default(),
+                    font.clone(),
+                    TextColor(PLAYER_COLORS[id.index()]),
+                ));
+            }
+            if *mode != GameMode::Solo {
+                parent.spawn((TeamHud, Text::default(), font.clone()));
+            }
+        });
+}
+
+pub fn update_hud(
+    mut player_hud_query: Query<(&mut Text, &PlayerHud), Without<TeamHud>>,
+    mut team_hud_query: Query<&mut Text, With<TeamHud>>,
+    player_query: Query<&Player>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+) {
+    for (mut text, hud) in player_hud_query.iter_mut() {
+        let stars = score.players[hud.0.index()];
+        let lives = match player_query.iter().find(|player| player.id == hud.0) {
+            Some(player) => format!("{} lives", player.lives),
+            None => "out".to_string(),
+        };
+        text.0 = format!("Player {}: {stars} stars, {lives}", hud.0.number());
+    }
+
+    for mut text in team_hud_query.iter_mut() {
+        text.0 = match *mode {
+            GameMode::Versus => {
+                let [one, two] = score.players;
+                match one.cmp(&two) {
+                    std::cmp::Ordering::Greater => format!("Player {} leads", PlayerId::One.number()),
+                    std::cmp::Ordering::Less => format!("Player {} leads", PlayerId::Two.number()),
+                    std::cmp::Ordering::Equal => "Tied".to_string(),
+                }
+            }
+            _ => format!("Team: {} stars", score.value),
+        };
+    }
+}
@@ -0,0 +1,160 @@
+pub mod framerate;
+pub mod actions;
+pub mod assets;
+pub mod attract;
+pub mod bench;
+pub mod bot;
+pub mod diagnostics;
+pub mod events;
+pub mod ghost;
+pub mod headless;
+pub mod hud;
+pub mod mode;
+pub mod net;
+pub mod replay;
+pub mod rng;
+pub mod save;
+mod systems;
+
+pub mod enemy;
+pub mod player;
+pub mod score;
+pub mod star;
+
+use actions::*;
+use events::*;
+use systems::*;
+
+use attract::AttractPlugin;
+use bot::BotPlugin;
+use diagnostics::FrameOverlayPlugin;
+use enemy::EnemyPlugin;
+use enemy::components::Enemy;
+use ghost::GhostPlugin;
+use hud::HudPlugin;
+use mode::GameMode;
+use net::NetPlugin;
+use net::client::NetClient;
+use player::PlayerPlugin;
+use player::components::Player;
+use replay::ReplayPlugin;
+use rng::GameRng;
+use save::{SAVE_PATH, SavePlugin};
+use score::ScorePlugin;
+use score::resources::Score;
+use star::StarPlugin;
+use star::components::Star;
+
+use assets::BallGameAssets;
+use action_map::ActionMapPlugin;
+use bevy::prelude::*;
+use common::{AddAssetsExt, AddGameExt, AddSoundExt, AppState, GamePlugin, Sound, exit_on, spawn_camera};
+use stepping::SteppingPlugin;
+
+// name of the game in the launcher
+pub const NAME: &str = "ball-game";
+// every system of the game only runs in this state
+pub const STATE: AppState = AppState::Playing(NAME);
+// the game starts here, loading its assets
+pub const LOADING: AppState = AppState::Loading(NAME);
+// looping while the game runs
+pub const MUSIC: &str = "ball-game/music";
+
+// the gameplay runs on the fixed time step in this order, so a replay plays out the same
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BallGameSet {
+    // turning the inputs into the players' intents
+    Input,
+    // recording the intent, or replacing it with a recorded one
+    Replay,
+    Player,
+    // following the player and the ghost of the best run
+    Ghost,
+    Enemy,
+    Star,
+    // checking the world state against the replay
+    Checksum,
+}
+
+pub struct BallGamePlugin;
+
+impl Plugin for BallGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ActionMapPlugin::<GameAction>::new(CONTROLS_PATH))
+            .scope_actions::<GameAction>(STATE)
+            .load_assets::<BallGameAssets>(NAME)
+            .add_sound(MUSIC, Sound::music("audio/music.ogg").volume(0.5))
+            .play_music_in(MUSIC, STATE)
+            .add_event::<GameOver>()
+            .init_resource::<GameRng>()
+            .init_resource::<GameMode>()
+            .register_type::<GameMode>()
+            .configure_sets(FixedUpdate, (
+                BallGameSet::Input,
+                BallGameSet::Replay,
+                BallGameSet::Player,
+                BallGameSet::Ghost,
+                BallGameSet::Enemy,
+                BallGameSet::Star,
+                BallGameSet::Checksum,
+            ).chain().run_if(in_state(STATE)))
+            // the spawns draw from the seeded rng in this order
+            .configure_sets(OnEnter(STATE), (
+                BallGameSet::Replay,
+                BallGameSet::Player,
+                BallGameSet::Enemy,
+                BallGameSet::Star,
+            ).chain())
+            .add_plugins(EnemyPlugin)
+            .add_plugins(PlayerPlugin)
+            .add_plugins(GhostPlugin)
+            .add_plugins(HudPlugin)
+            .add_plugins(ScorePlugin)
+            .add_plugins(StarPlugin)
+            .add_plugins(FrameOverlayPlugin)
+            .add_plugins(ReplayPlugin)
+            .add_plugins(SavePlugin)
+            .add_plugins(NetPlugin)
+            .add_plugins(AttractPlugin)
+            .add_plugins(BotPlugin)
+            .add_systems(OnEnter(STATE), (spawn_camera(STATE), reset_clear_color))
+            .add_systems(Update, exit_on(GameAction::Exit).run_if(in_state(STATE)))
+            .add_systems(Update, handle_game_over.run_if(in_state(STATE)))
+            // a client plays the server's mode
+            .add_systems(Update, cycle_mode.run_if(in_state(STATE).and(not(resource_exists::<NetClient>))));
+    }
+}
+
+impl GamePlugin for BallGamePlugin {
+    fn title(&self) -> &'static str {
+        NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "dodge the enemies and collect the stars, alone or with a friend"
+    }
+
+    fn save_path(&self) -> Option<&'static str> {
+        Some(SAVE_PATH)
+    }
+
+    fn attract_mode(&self) -> bool {
+        true
+    }
+}
+
+// step through the game systems with `--features bevy_debug_stepping`
+pub fn stepping_plugin() -> SteppingPlugin {
+    SteppingPlugin::default()
+        .add_schedule(FixedUpdate)
+        .add_schedule(Update)
+        .show_systems(|name| name.starts_with("ball_game"))
+        .break_on_event::<GameOver>("game over")
+        .inspect_component::<Transform, With<Player>>()
+        .inspect_component::<Player, ()>()
+        .inspect_count::<With<Enemy>>()
+        .inspect_count::<With<Star>>()
+        .inspect_resource::<Score>()
+        .show_hint_in(STATE)
+        .at(Val::Percent(2.0), Val::Percent(10.0))
+}
@@ -1,19 +1,14 @@
-// mod framerate;  //load framerate.rs
-pub mod events;
-mod systems;
-
-pub mod enemy;
-mod player;
-pub mod score;
-pub mod star;
-
-use events::*;
-use systems::*;
-
-use enemy::EnemyPlugin;
-use player::PlayerPlugin;
-use score::ScorePlugin;
-use star::StarPlugin;
+use ball_game::{BallGamePlugin, LOADING, NAME, stepping_plugin};
+use ball_game::framerate::{FramePacingPlugin, PacingMode};
+use ball_game::bench::{self, BenchConfig};
+use ball_game::bot::{Bot, Policy, policies};
+use ball_game::bot::runner::{self, RunnerConfig};
+use ball_game::mode::GameMode;
+use ball_game::net::client::NetClient;
+use ball_game::net::{DEFAULT_ADDR, ServerConfig, run_server};
+use ball_game::replay::resources::{Replay, ReplayMode};
+use common::assets::ASSET_FOLDER;
+use common::{AddGameExt, Attract, ContinueGame};
 
 use bevy::prelude::*;
 
@@ -22,8 +17,6 @@ use bevy::prelude::*;
 use bevy::window::{WindowPlugin, PresentMode, Window, WindowResolution};
 use std::num::NonZeroU32;
 // use rand::random;
-// use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, DiagnosticsStore};
-// use framerate::FrameLimiterPlugin;
 
 //use framerate::{FramepacePlugin, FramepaceSettings, Limiter};
 // ensure stars aren't too close to a spawned enemy
@@ -38,21 +31,142 @@ use std::num::NonZeroU32;
 // ---------------------------------------------------------------------------
 // Helper functions
 // ---------------------------------------------------------------------------
-// fn fps_system(diagnostics: Res<DiagnosticsStore>) {
-//     if let Some(fps) = diagnostics
-//         .get(&FrameTimeDiagnosticsPlugin::FPS)
-//         .and_then(|d| d.average())
-//     {
-//         info!("FPS: {:.2}", fps);
-//     }
-// }
+// the fps overlay lives in diagnostics, toggle it with F3
 
 // ---------------------------------------------------------------------------
 // Start the application
 // ---------------------------------------------------------------------------
-fn main() {
+// the arguments of the windowed game, which can be combined, e.g. `--mode coop --bot greedy`
+#[derive(Default)]
+struct PlayConfig {
+    // watch a bot play, e.g. `--bot potential`
+    bot: Option<Box<dyn Policy>>,
+    // join a server, e.g. `--connect 127.0.0.1:5888`
+    connect: Option<String>,
+    // play a recorded run back, e.g. `--replay replays/last.replay`
+    replay: Option<String>,
+    // play together or against each other, e.g. `--mode coop`
+    mode: Option<GameMode>,
+    // continue the round saved when the game was last left
+    continue_game: bool,
+    // the demo the launcher shows when idle, playing itself
+    demo: bool,
+}
 
-    App::new().add_plugins(DefaultPlugins
+impl PlayConfig {
+    const USAGE: &str = "usage: ball-game [--mode solo|coop|versus] [--bot greedy|potential] \
+        [--connect [HOST:PORT] | --replay <file>] [--continue] [--demo]\n       \
+        ball-game --bench|--bots|--server [...]";
+
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = PlayConfig::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("missing value for {arg}"));
+            match arg.as_str() {
+                "--bot" => {
+                    let name = value()?;
+                    config.bot = Some(policies::from_name(&name).ok_or_else(|| format!("unknown bot {name}"))?);
+                }
+                "--replay" => config.replay = Some(value()?),
+                "--mode" => {
+                    let name = value()?;
+                    config.mode = Some(GameMode::from_name(&name).ok_or_else(|| format!("unknown mode {name}"))?);
+                }
+                // the address is optional
+                "--connect" => config.connect = Some(args.next_if(|next| !next.starts_with("--")).unwrap_or_else(|| DEFAULT_ADDR.to_string())),
+                "--continue" => config.continue_game = true,
+                "--demo" => config.demo = true,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        // both decide what happens to the runs
+        if config.connect.is_some() && config.replay.is_some() {
+            return Err("--connect and --replay can't be combined".to_string());
+        }
+        Ok(config)
+    }
+}
+
+fn exit_with_usage(error: impl std::fmt::Display, usage: &str) -> ! {
+    eprintln!("{error}\n{usage}");
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    // the headless runs take the rest of the arguments
+    match args.peek().map(String::as_str) {
+        // headless benchmark instead of the game
+        Some("--bench") => {
+            args.next();
+            match BenchConfig::parse(args) {
+                Ok(config) => bench::run(config),
+                Err(error) => exit_with_usage(error, BenchConfig::USAGE),
+            }
+            return;
+        }
+        // bots playing many rounds headless, e.g. `--bots --policy greedy --episodes 50`
+        Some("--bots") => {
+            args.next();
+            match RunnerConfig::parse(args) {
+                Ok(config) => runner::run(config),
+                Err(error) => exit_with_usage(error, RunnerConfig::USAGE),
+            }
+            return;
+        }
+        // run the game for the clients over UDP, without a window
+        Some("--server") => {
+            args.next();
+            let config = ServerConfig::parse(args).unwrap_or_else(|error| exit_with_usage(error, ServerConfig::USAGE));
+            if let Err(error) = run_server(config) {
+                eprintln!("couldn't serve: {error}");
+                std::process::exit(2);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let config = PlayConfig::parse(args).unwrap_or_else(|error| exit_with_usage(error, PlayConfig::USAGE));
+    let mut replay_mode = ReplayMode::Record;
+    let mut app = App::new();
+    if let Some(policy) = config.bot {
+        app.insert_resource(Bot(policy));
+    }
+    if let Some(addr) = config.connect {
+        match NetClient::connect(addr.as_str()) {
+            Ok(client) => {
+                app.insert_resource(client);
+                // the round is played on the server
+                replay_mode = ReplayMode::Off;
+            }
+            Err(error) => {
+                eprintln!("couldn't connect to {addr}: {error}");
+                std::process::exit(2);
+            }
+        }
+    }
+    if let Some(path) = config.replay {
+        match Replay::load(path.as_ref()) {
+            Ok(replay) => replay_mode = ReplayMode::Playback(replay),
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(2);
+            }
+        }
+    }
+    if let Some(mode) = config.mode {
+        app.insert_resource(mode);
+    }
+    if config.continue_game {
+        app.insert_resource(ContinueGame(NAME));
+    }
+    if config.demo {
+        app.insert_resource(Attract(NAME));
+    }
+
+    app.add_plugins(DefaultPlugins
             .set(WindowPlugin {
                 primary_window: Some(Window {
                     resolution: WindowResolution::new(800.0, 800.0),
@@ -65,27 +179,21 @@ fn main() {
                 ..Default::default()
             })
             .set(AssetPlugin{
-                //note assets/ is the default directory that bevy looks in
-                //for preprocessed assets
-                //we can change this as needed
+                //note the assets of every game are in the workspace's assets/ folder,
+                //shared with the launcher
                 //also note that with cross compilation can complicate things
-                file_path: "assets".to_string(),
+                file_path: ASSET_FOLDER.to_string(),
                 ..Default::default()
             }),
             )
-        .add_event::<GameOver>()
-        .add_plugins(EnemyPlugin)
-        .add_plugins(PlayerPlugin)
-        .add_plugins(ScorePlugin)
-        .add_plugins(StarPlugin)
-        .add_systems(Startup, spawn_camera)
-        .add_systems(Update, exit_game)
-        .add_systems(Update, handle_game_over)
+        .insert_state(LOADING)
+        .add_game(BallGamePlugin)
+        .insert_resource(replay_mode)
+        .add_plugins(stepping_plugin())
+        // cap framerate at 120fps, vsync is off
+        .add_plugins(FramePacingPlugin::new(PacingMode::Fixed(120.0)))
         //.init_resource::<Score>()
         //.init_resource::<StarSpawnTimer>()
-        //.add_plugins(FrameTimeDiagnosticsPlugin::default())
-        //// cap framerate at 120fps
-        ////.add_plugins(FrameLimiterPlugin::with_fps(120.0))
         //.add_systems(Startup, spawn_camera)
         //.add_systems(Startup, spawn_player)
         //.add_systems(Startup, spawn_enemies)
@@ -96,7 +204,6 @@ fn main() {
         //.add_systems(Update, (update_enemy_direction, enemy_hit_star).after(enemy_movement).before(confine_enemy_movement))
         //.add_systems(Update, (confine_player_movement, confine_enemy_movement ))
         //.add_systems(Update, enemy_hit_player)
-        //// .add_systems(Update, fps_system)
         //.add_systems(Update, player_hit_star)
         //.add_systems(Update, update_score)
         //.add_systems(Update, spawn_stars_over_time)
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::components::PlayerId;
+
+// how many play, and whether they play together or against each other
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Solo,
+    // both players collect the same stars for a shared score
+    Coop,
+    // each player scores their own stars, and bumping into the other steals one
+    Versus,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Solo, GameMode::Coop, GameMode::Versus];
+
+    pub fn players(self) -> &'static [PlayerId] {
+        match self {
+            GameMode::Solo => &[PlayerId::One],
+            GameMode::Coop | GameMode::Versus => &PlayerId::ALL,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            GameMode::Solo => GameMode::Coop,
+            GameMode::Coop => GameMode::Versus,
+            GameMode::Versus => GameMode::Solo,
+        }
+    }
+
+    // the `--mode` argument naming it
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Solo => "solo",
+            GameMode::Coop => "coop",
+            GameMode::Versus => "versus",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        GameMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    // how replays and the network protocol write it
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        GameMode::ALL.get(byte as usize).copied()
+    }
+}
@@ -0,0 +1,351 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use super::protocol::{ClientMessage, MAX_INPUTS, ServerMessage, Snapshot};
+use super::{CLIENT_TIMEOUT, DATAGRAM_SIZE, INTERPOLATION_DELAY};
+use crate::STATE;
+use crate::assets::BallGameAssets;
+use crate::enemy::components::Enemy;
+use crate::mode::GameMode;
+use crate::player::components::{Player, PlayerId};
+use crate::player::resources::{PlayerIntent, PlayerIntents};
+use crate::player::{player_sprite, step_player};
+use crate::score::resources::Score;
+use crate::star::components::Star;
+
+// between two hellos, until the server answers
+const HELLO_INTERVAL: Duration = Duration::from_millis(500);
+// snapshots kept to interpolate between, a few more than the delay
+const SNAPSHOT_BUFFER: usize = 4 * INTERPOLATION_DELAY as usize;
+
+// a client playing on a server, which runs the game and sends back snapshots of it
+#[derive(Resource)]
+pub struct NetClient {
+    socket: UdpSocket,
+    server: SocketAddr,
+    welcome: Option<Welcome>,
+    // the server had no player left
+    refused: bool,
+    last_hello: Option<Instant>,
+    last_heard: Instant,
+    // client ticks played, one input each
+    tick: u32,
+    // inputs the server hasn't applied yet, by client tick
+    pending: VecDeque<(u32, PlayerIntent)>,
+    // oldest first
+    snapshots: VecDeque<Snapshot>,
+    // whether the newest snapshot is still to be reconciled with
+    reconcile: bool,
+    // the server tick the remote entities are shown at, behind the newest snapshot
+    render_tick: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Welcome {
+    player: PlayerId,
+    arena: Vec2,
+}
+
+// a copy of a server entity, moved to where the snapshots say
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Remote {
+    Player(PlayerId),
+    Enemy(u64),
+    Star(u64),
+}
+
+impl NetClient {
+    pub fn connect(server: impl ToSocketAddrs) -> io::Result<Self> {
+        let server = server
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address for the server"))?;
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_nonblocking(true)?;
+        Ok(NetClient {
+            socket,
+            server,
+            welcome: None,
+            refused: false,
+            last_hello: None,
+            last_heard: Instant::now(),
+            tick: 0,
+            pending: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            reconcile: false,
+            render_tick: 0.0,
+        })
+    }
+
+    // the player the server gave this client, once it joined
+    pub fn player(&self) -> Option<PlayerId> {
+        self.welcome.map(|welcome| welcome.player)
+    }
+
+    pub fn refused(&self) -> bool {
+        self.refused
+    }
+
+    fn send(&self, message: &ClientMessage) {
+        if let Err(error) = self.socket.send_to(&message.to_bytes(), self.server) {
+            warn!("Couldn't send to the server at {}: {error}", self.server);
+        }
+    }
+}
+
+// say hello until welcomed
+pub fn join_server(mut client: ResMut<NetClient>) {
+    if client.welcome.is_some() || client.refused {
+        return;
+    }
+    if client.last_hello.is_none_or(|last| last.elapsed() >= HELLO_INTERVAL) {
+        client.send(&ClientMessage::Hello);
+        client.last_hello = Some(Instant::now());
+    }
+}
+
+pub fn receive_server_messages(mut client: ResMut<NetClient>, mut mode: ResMut<GameMode>) {
+    let mut buffer = [0; DATAGRAM_SIZE];
+    loop {
+        let length = match client.socket.recv_from(&mut buffer) {
+            Ok((length, addr)) if addr == client.server => length,
+            Ok(_) => continue,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            Err(error) => {
+                debug!("Couldn't receive from the server: {error}");
+                continue;
+            }
+        };
+        let message = match ServerMessage::from_bytes(&buffer[..length]) {
+            Ok(message) => message,
+            Err(error) => {
+                warn!("Ignoring a datagram from the server: {error}");
+                continue;
+            }
+        };
+        client.last_heard = Instant::now();
+
+        match message {
+            ServerMessage::Welcome { player, mode: server_mode, arena } => {
+                if client.welcome.is_none() {
+                    info!("Joined {} as player {} in {}", client.server, player.number(), server_mode.name());
+                }
+                client.welcome = Some(Welcome { player, arena });
+                // the players and the hud follow the server's mode
+                if *mode != server_mode {
+                    *mode = server_mode;
+                }
+            }
+            ServerMessage::Full => {
+                if !client.refused {
+                    warn!("The server at {} is full", client.server);
+                }
+                client.refused = true;
+            }
+            ServerMessage::Snapshot(snapshot) => {
+                // datagrams can arrive out of order
+                if client.snapshots.back().is_some_and(|newest| newest.tick >= snapshot.tick) {
+                    continue;
+                }
+                client.snapshots.push_back(snapshot);
+                if client.snapshots.len() > SNAPSHOT_BUFFER {
+                    client.snapshots.pop_front();
+                }
+                client.reconcile = true;
+            }
+        }
+    }
+
+    if client.welcome.is_some() && client.last_heard.elapsed() > Duration::from_secs_f32(CLIENT_TIMEOUT) {
+        warn!("Lost the server at {}, joining again", client.server);
+        client.welcome = None;
+        client.last_heard = Instant::now();
+    }
+}
+
+// the server's state of the round, and which players and lives are left
+pub fn apply_snapshot(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    assets: Res<BallGameAssets>,
+    mut score: ResMut<Score>,
+    mut player_query: Query<(Entity, &mut Player)>,
+) {
+    let Some(snapshot) = client.snapshots.back() else {
+        return;
+    };
+    if *score != snapshot.score {
+        *score = snapshot.score.clone();
+    }
+
+    for (entity, mut player) in player_query.iter_mut() {
+        match snapshot.players.iter().find(|state| state.id == player.id) {
+            Some(state) if player.lives != state.lives => player.lives = state.lives,
+            Some(_) => {}
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    for state in &snapshot.players {
+        if player_query.iter().all(|(_, player)| player.id != state.id) {
+            commands.spawn((
+                player_sprite(state.id, &assets),
+                Transform::from_translation(state.position.extend(0.0)),
+                Player { id: state.id, lives: state.lives },
+                Remote::Player(state.id),
+                StateScoped(STATE),
+            ));
+        }
+    }
+}
+
+// move the local player right away instead of waiting for the server, then correct it by
+// replaying the inputs the server hasn't applied yet over its newest position
+pub fn predict_local_player(
+    mut client: ResMut<NetClient>,
+    intents: Res<PlayerIntents>,
+    mut player_query: Query<(&mut Transform, &Player)>,
+    time: Res<Time>,
+) {
+    let Some(welcome) = client.welcome else {
+        return;
+    };
+    // the local player has player one's controls, whichever player it is
+    let intent = intents.get(PlayerId::One);
+    client.tick += 1;
+    let tick = client.tick;
+    client.pending.push_back((tick, intent));
+
+    let delta_secs = time.delta_secs();
+    let reconcile = std::mem::take(&mut client.reconcile);
+    let acked = client.snapshots.back().map(|snapshot| {
+        let position = snapshot
+            .players
+            .iter()
+            .find(|state| state.id == welcome.player)
+            .map(|state| state.position);
+        (snapshot.acked_input, position)
+    });
+    if let Some((acked_input, _)) = acked {
+        while client.pending.front().is_some_and(|&(tick, _)| tick <= acked_input) {
+            client.pending.pop_front();
+        }
+    }
+
+    if let Some((mut transform, _)) = player_query.iter_mut().find(|(_, player)| player.id == welcome.player) {
+        match acked {
+            Some((_, Some(position))) if reconcile => {
+                let mut translation = position.extend(transform.translation.z);
+                for &(_, intent) in &client.pending {
+                    translation = step_player(translation, intent, delta_secs, welcome.arena);
+                }
+                transform.translation = translation;
+            }
+            _ => transform.translation = step_player(transform.translation, intent, delta_secs, welcome.arena),
+        }
+    }
+
+    let skipped = client.pending.len().saturating_sub(MAX_INPUTS);
+    let first_tick = client.pending.get(skipped).map_or(tick, |&(tick, _)| tick);
+    let message = ClientMessage::Inputs {
+        first_tick,
+        intents: client.pending.iter().skip(skipped).map(|&(_, intent)| intent).collect(),
+    };
+    client.send(&message);
+}
+
+// show the other entities between the two snapshots around the render tick, a little in the
+// past, so they move smoothly whenever the snapshots arrive
+#[allow(clippy::type_complexity)]
+pub fn interpolate_remote_entities(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    assets: Res<BallGameAssets>,
+    mut remote_query: Query<(Entity, &Remote, &mut Transform)>,
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let Some(newest) = client.snapshots.back().map(|snapshot| snapshot.tick) else {
+        return;
+    };
+    let local = client.player();
+
+    // keep time with the server, unless it drifted too far
+    let target = newest as f32 - INTERPOLATION_DELAY as f32;
+    client.render_tick += time.delta_secs() / fixed_time.timestep().as_secs_f32();
+    if (client.render_tick - target).abs() > INTERPOLATION_DELAY as f32 {
+        client.render_tick = target;
+    }
+    client.render_tick = client.render_tick.min(newest as f32);
+
+    let render_tick = client.render_tick;
+    let Some(from) = client.snapshots.iter().rev().find(|snapshot| snapshot.tick as f32 <= render_tick)
+        .or(client.snapshots.front()) else {
+        return;
+    };
+    let to = client.snapshots.iter().find(|snapshot| snapshot.tick > from.tick);
+    let blend = to.map_or(0.0, |to| {
+        ((render_tick - from.tick as f32) / (to.tick - from.tick) as f32).clamp(0.0, 1.0)
+    });
+
+    let positions = |snapshot: &Snapshot| -> HashMap<Remote, Vec2> {
+        let players = snapshot.players.iter().map(|state| (Remote::Player(state.id), state.position));
+        let enemies = snapshot.enemies.iter().map(|&(id, position)| (Remote::Enemy(id), position));
+        let stars = snapshot.stars.iter().map(|&(id, position)| (Remote::Star(id), position));
+        players.chain(enemies).chain(stars).collect()
+    };
+    let mut positions_from = positions(from);
+    let positions_to = to.map(positions).unwrap_or_default();
+
+    for (entity, remote, mut transform) in remote_query.iter_mut() {
+        // the local player is predicted instead, the players come and go with the newest snapshot
+        if let Remote::Player(id) = remote {
+            if Some(*id) != local
+                && let Some(&position) = positions_from.get(remote)
+            {
+                let position = position.lerp(*positions_to.get(remote).unwrap_or(&position), blend);
+                transform.translation = position.extend(transform.translation.z);
+            }
+            positions_from.remove(remote);
+            continue;
+        }
+        match positions_from.remove(remote) {
+            Some(position) => {
+                let position = position.lerp(*positions_to.get(remote).unwrap_or(&position), blend);
+                transform.translation = position.extend(transform.translation.z);
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    // the players are spawned with the newest snapshot instead
+    for (remote, position) in positions_from {
+        if let Remote::Player(_) = remote {
+            continue;
+        }
+        let mut entity = commands.spawn((
+            Transform::from_translation(position.extend(0.0)),
+            remote,
+            StateScoped(STATE),
+        ));
+        match remote {
+            // they only move where the server says
+            Remote::Enemy(_) => {
+                entity.insert((Sprite::from_image(assets.enemy.clone()), Enemy { direction: Vec2::ZERO }));
+            }
+            Remote::Star(_) => {
+                entity.insert((Sprite::from_image(assets.star.clone()), Star {}));
+            }
+            Remote::Player(_) => {}
+        }
+    }
+}
+
+// let the server free the player right away
+pub fn leave_server(client: Res<NetClient>) {
+    if client.welcome.is_some() {
+        client.send(&ClientMessage::Bye);
+    }
+}
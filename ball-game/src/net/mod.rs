@@ -0,0 +1,128 @@
+// playing over UDP: a headless server runs the game and clients join it as its players
+//
+// the server is authoritative, it simulates the whole round from the inputs the clients send
+// every tick, and sends each client a snapshot of the world after every tick. the clients don't
+// simulate the enemies and stars, they show them between two snapshots a few ticks in the past
+// so they move smoothly. the local player is predicted right away from the client's own inputs,
+// and corrected with each snapshot by replaying the inputs the server hasn't applied yet.
+
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+
+use crate::headless::headless_app;
+use crate::mode::GameMode;
+use crate::replay::resources::ReplayMode;
+use crate::{BallGameSet, STATE};
+
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+use client::*;
+use server::*;
+
+// where the server listens unless told otherwise
+pub const DEFAULT_ADDR: &str = "127.0.0.1:5888";
+// the largest datagram over UDP
+pub const DATAGRAM_SIZE: usize = 65_507;
+// seconds without hearing from the other side before giving up on it
+pub const CLIENT_TIMEOUT: f32 = 5.0;
+// ticks the remote entities are shown behind the newest snapshot
+pub const INTERPOLATION_DELAY: u32 = 6;
+
+// the server runs the game for `NetServer` and a client follows it for `NetClient`, whichever
+// resource is inserted
+pub struct NetPlugin;
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        let serving = resource_exists::<NetServer>.and(in_state(STATE));
+        let joined = resource_exists::<NetClient>.and(in_state(STATE));
+
+        // only the server simulates the round
+        app.configure_sets(FixedUpdate, (
+                BallGameSet::Replay,
+                BallGameSet::Player,
+                BallGameSet::Ghost,
+                BallGameSet::Enemy,
+                BallGameSet::Star,
+                BallGameSet::Checksum,
+            ).run_if(not(resource_exists::<NetClient>)))
+            .configure_sets(OnEnter(STATE), (
+                BallGameSet::Replay,
+                BallGameSet::Player,
+                BallGameSet::Enemy,
+                BallGameSet::Star,
+            ).run_if(not(resource_exists::<NetClient>)))
+            .add_systems(FixedUpdate, (
+                receive_client_messages,
+                apply_client_inputs,
+            ).chain().after(BallGameSet::Input).before(BallGameSet::Replay).run_if(serving.clone()))
+            .add_systems(FixedUpdate, send_snapshots.after(BallGameSet::Checksum).run_if(serving.clone()))
+            .add_systems(Update, restart_on_game_over.run_if(serving))
+            .add_systems(FixedUpdate, (
+                receive_server_messages,
+                apply_snapshot,
+                predict_local_player,
+            ).chain().after(BallGameSet::Input).run_if(joined.clone()))
+            .add_systems(Update, (join_server, interpolate_remote_entities).run_if(joined))
+            .add_systems(OnExit(STATE), leave_server.run_if(resource_exists::<NetClient>));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub addr: String,
+    pub mode: GameMode,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            addr: DEFAULT_ADDR.to_string(),
+            mode: GameMode::Coop,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub const USAGE: &str = "usage: ball-game --server [--addr HOST:PORT] [--mode coop|versus]";
+
+    // the arguments after `--server`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = ServerConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            match arg.as_str() {
+                "--addr" => config.addr = value,
+                "--mode" => {
+                    config.mode = GameMode::from_name(&value)
+                        .filter(|mode| mode.players().len() > 1)
+                        .ok_or_else(|| format!("invalid value for {arg}: {value}"))?;
+                }
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// runs the headless server until it's stopped
+pub fn run_server(config: ServerConfig) -> std::io::Result<()> {
+    let server = NetServer::bind(&config.addr)?;
+    let addr = server.local_addr()?;
+
+    let mut app = headless_app();
+    // the headless game doesn't log otherwise
+    app.add_plugins(LogPlugin::default());
+    info!("Serving a {} round on {addr}", config.mode.name());
+    app.insert_resource(server)
+        .insert_resource(config.mode)
+        // the clients' runs aren't recorded
+        .insert_resource(ReplayMode::Off);
+    app.run();
+    Ok(())
+}
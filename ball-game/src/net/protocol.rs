@@ -0,0 +1,372 @@
+use bevy::prelude::*;
+
+use crate::mode::GameMode;
+use crate::player::components::PlayerId;
+use crate::player::resources::PlayerIntent;
+use crate::score::resources::Score;
+
+// start of every datagram, followed by the protocol version
+const MAGIC: &[u8; 4] = b"BGNT";
+const VERSION: u8 = 1;
+// inputs a client resends in each datagram, in case some are lost
+pub const MAX_INPUTS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    // asking to join, repeated until welcomed
+    Hello,
+    // the intents of the local player from `first_tick` on, one per client tick
+    Inputs {
+        first_tick: u32,
+        intents: Vec<PlayerIntent>,
+    },
+    // leaving, so the player is free for someone else
+    Bye,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Welcome {
+        player: PlayerId,
+        mode: GameMode,
+        // size of the server's arena, the players are kept inside it
+        arena: Vec2,
+    },
+    // every player is taken
+    Full,
+    Snapshot(Snapshot),
+}
+
+// the world on the server after one of its ticks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub tick: u32,
+    // the last client tick whose input the server applied, for the client receiving it
+    pub acked_input: u32,
+    pub score: Score,
+    pub players: Vec<PlayerState>,
+    // the server entity and its position
+    pub enemies: Vec<(u64, Vec2)>,
+    pub stars: Vec<(u64, Vec2)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerState {
+    pub id: PlayerId,
+    pub lives: u32,
+    pub position: Vec2,
+}
+
+impl ClientMessage {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = header();
+        match self {
+            ClientMessage::Hello => bytes.push(0),
+            ClientMessage::Inputs { first_tick, intents } => {
+                bytes.push(1);
+                bytes.extend_from_slice(&first_tick.to_le_bytes());
+                bytes.push(intents.len().min(MAX_INPUTS) as u8);
+                for intent in intents.iter().take(MAX_INPUTS) {
+                    bytes.extend_from_slice(&intent.x.to_le_bytes());
+                    bytes.extend_from_slice(&intent.y.to_le_bytes());
+                }
+            }
+            ClientMessage::Bye => bytes.push(2),
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes)?;
+        let [kind] = reader.take::<1>()?;
+        let message = match kind {
+            0 => ClientMessage::Hello,
+            1 => {
+                let first_tick = u32::from_le_bytes(reader.take()?);
+                let [count] = reader.take::<1>()?;
+                let mut intents = Vec::new();
+                for _ in 0..count {
+                    intents.push(PlayerIntent {
+                        x: i8::from_le_bytes(reader.take()?),
+                        y: i8::from_le_bytes(reader.take()?),
+                    });
+                }
+                ClientMessage::Inputs { first_tick, intents }
+            }
+            2 => ClientMessage::Bye,
+            _ => return Err(format!("unknown client message {kind}")),
+        };
+        reader.finish()?;
+        Ok(message)
+    }
+}
+
+impl ServerMessage {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = header();
+        match self {
+            ServerMessage::Welcome { player, mode, arena } => {
+                bytes.push(0);
+                bytes.push(player.index() as u8);
+                bytes.push(mode.to_byte());
+                write_vec2(&mut bytes, *arena);
+            }
+            ServerMessage::Full => bytes.push(1),
+            ServerMessage::Snapshot(snapshot) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&snapshot.tick.to_le_bytes());
+                bytes.extend_from_slice(&snapshot.acked_input.to_le_bytes());
+                bytes.extend_from_slice(&snapshot.score.value.to_le_bytes());
+                for stars in snapshot.score.players {
+                    bytes.extend_from_slice(&stars.to_le_bytes());
+                }
+                bytes.push(snapshot.players.len() as u8);
+                for player in &snapshot.players {
+                    bytes.push(player.id.index() as u8);
+                    bytes.extend_from_slice(&player.lives.to_le_bytes());
+                    write_vec2(&mut bytes, player.position);
+                }
+                for entities in [&snapshot.enemies, &snapshot.stars] {
+                    bytes.extend_from_slice(&(entities.len() as u32).to_le_bytes());
+                    for &(id, position) in entities {
+                        bytes.extend_from_slice(&id.to_le_bytes());
+                        write_vec2(&mut bytes, position);
+                    }
+                }
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes)?;
+        let [kind] = reader.take::<1>()?;
+        let message = match kind {
+            0 => {
+                let player = reader.player()?;
+                let [mode] = reader.take::<1>()?;
+                let mode = GameMode::from_byte(mode).ok_or(format!("unknown game mode {mode}"))?;
+                ServerMessage::Welcome {
+                    player,
+                    mode,
+                    arena: reader.vec2()?,
+                }
+            }
+            1 => ServerMessage::Full,
+            2 => {
+                let tick = u32::from_le_bytes(reader.take()?);
+                let acked_input = u32::from_le_bytes(reader.take()?);
+                let mut score = Score {
+                    value: u32::from_le_bytes(reader.take()?),
+                    ..default()
+                };
+                for stars in &mut score.players {
+                    *stars = u32::from_le_bytes(reader.take()?);
+                }
+                let [count] = reader.take::<1>()?;
+                let mut players = Vec::new();
+                for _ in 0..count {
+                    players.push(PlayerState {
+                        id: reader.player()?,
+                        lives: u32::from_le_bytes(reader.take()?),
+                        position: reader.vec2()?,
+                    });
+                }
+                let mut entities = [Vec::new(), Vec::new()];
+                for list in &mut entities {
+                    for _ in 0..u32::from_le_bytes(reader.take()?) {
+                        list.push((u64::from_le_bytes(reader.take()?), reader.vec2()?));
+                    }
+                }
+                let [enemies, stars] = entities;
+                ServerMessage::Snapshot(Snapshot {
+                    tick,
+                    acked_input,
+                    score,
+                    players,
+                    enemies,
+                    stars,
+                })
+            }
+            _ => return Err(format!("unknown server message {kind}")),
+        };
+        reader.finish()?;
+        Ok(message)
+    }
+}
+
+fn header() -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes
+}
+
+fn write_vec2(bytes: &mut Vec<u8>, value: Vec2) {
+    bytes.extend_from_slice(&value.x.to_le_bytes());
+    bytes.extend_from_slice(&value.y.to_le_bytes());
+}
+
+// the bytes of a datagram left to read
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    // past the header
+    fn new(bytes: &'a [u8]) -> Result<Self, String> {
+        let mut reader = Reader(bytes);
+        if reader.take::<4>()? != *MAGIC {
+            return Err("not a ball-game datagram".to_string());
+        }
+        let [version] = reader.take::<1>()?;
+        if version != VERSION {
+            return Err(format!("unsupported protocol version {version}"));
+        }
+        Ok(reader)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let Some((bytes, rest)) = self.0.split_first_chunk::<N>() else {
+            return Err("the datagram is cut short".to_string());
+        };
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    fn vec2(&mut self) -> Result<Vec2, String> {
+        Ok(Vec2::new(
+            f32::from_le_bytes(self.take()?),
+            f32::from_le_bytes(self.take()?),
+        ))
+    }
+
+    fn player(&mut self) -> Result<PlayerId, String> {
+        let [index] = self.take::<1>()?;
+        PlayerId::from_index(index as usize).ok_or(format!("unknown player {index}"))
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if !self.0.is_empty() {
+            return Err(format!("{} unexpected bytes at the end", self.0.len()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> ServerMessage {
+        ServerMessage::Snapshot(Snapshot {
+            tick: 1234,
+            acked_input: 1200,
+            score: Score {
+                value: 7,
+                players: [3, 4],
+            },
+            players: vec![
+                PlayerState {
+                    id: PlayerId::One,
+                    lives: 2,
+                    position: Vec2::new(-10.5, 20.0),
+                },
+                PlayerState {
+                    id: PlayerId::Two,
+                    lives: 0,
+                    position: Vec2::new(300.0, -0.25),
+                },
+            ],
+            enemies: vec![(5, Vec2::new(1.0, 2.0)), (u64::MAX, Vec2::ZERO)],
+            stars: vec![(9, Vec2::new(-3.0, 4.0))],
+        })
+    }
+
+    #[test]
+    fn client_messages_round_trip() {
+        let messages = [
+            ClientMessage::Hello,
+            ClientMessage::Inputs {
+                first_tick: 77,
+                intents: vec![PlayerIntent { x: 1, y: 0 }, PlayerIntent { x: -1, y: 1 }],
+            },
+            ClientMessage::Inputs {
+                first_tick: 0,
+                intents: Vec::new(),
+            },
+            ClientMessage::Bye,
+        ];
+        for message in messages {
+            assert_eq!(ClientMessage::from_bytes(&message.to_bytes()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn only_the_last_inputs_fitting_a_datagram_are_sent() {
+        let intents: Vec<_> = (0..MAX_INPUTS as i8 + 3)
+            .map(|x| PlayerIntent { x, y: 0 })
+            .collect();
+        let message = ClientMessage::Inputs { first_tick: 10, intents: intents.clone() };
+        assert_eq!(
+            ClientMessage::from_bytes(&message.to_bytes()),
+            Ok(ClientMessage::Inputs {
+                first_tick: 10,
+                intents: intents[..MAX_INPUTS].to_vec(),
+            })
+        );
+    }
+
+    #[test]
+    fn server_messages_round_trip() {
+        let messages = [
+            ServerMessage::Welcome {
+                player: PlayerId::Two,
+                mode: GameMode::Coop,
+                arena: Vec2::new(1280.0, 720.0),
+            },
+            ServerMessage::Full,
+            snapshot(),
+        ];
+        for message in messages {
+            assert_eq!(ServerMessage::from_bytes(&message.to_bytes()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn corrupt_datagrams_are_rejected() {
+        let hello = ClientMessage::Hello.to_bytes();
+        let snapshot = snapshot().to_bytes();
+        let with = |bytes: &[u8], index: usize, byte: u8| {
+            let mut bytes = bytes.to_vec();
+            bytes[index] = byte;
+            bytes
+        };
+        let mut trailing = hello.clone();
+        trailing.push(0);
+
+        let client_cases = [
+            (with(&hello, 0, b'X'), "not a ball-game datagram".to_string()),
+            (with(&hello, 4, VERSION + 1), format!("unsupported protocol version {}", VERSION + 1)),
+            (with(&hello, 5, 3), "unknown client message 3".to_string()),
+            (hello[..5].to_vec(), "the datagram is cut short".to_string()),
+            (trailing, "1 unexpected bytes at the end".to_string()),
+        ];
+        for (corrupt, error) in client_cases {
+            assert_eq!(ClientMessage::from_bytes(&corrupt), Err(error));
+        }
+
+        let welcome = ServerMessage::Welcome {
+            player: PlayerId::One,
+            mode: GameMode::Solo,
+            arena: Vec2::ONE,
+        }
+        .to_bytes();
+        let server_cases = [
+            (with(&snapshot, 5, 3), "unknown server message 3".to_string()),
+            (with(&welcome, 6, 9), "unknown player 9".to_string()),
+            (with(&welcome, 7, 9), "unknown game mode 9".to_string()),
+            (snapshot[..snapshot.len() - 1].to_vec(), "the datagram is cut short".to_string()),
+        ];
+        for (corrupt, error) in server_cases {
+            assert_eq!(ServerMessage::from_bytes(&corrupt), Err(error));
+        }
+    }
+}
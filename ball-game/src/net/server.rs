@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, Window};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use super::protocol::{ClientMessage, PlayerState, ServerMessage, Snapshot};
+use super::{CLIENT_TIMEOUT, DATAGRAM_SIZE};
+use crate::LOADING;
+use crate::enemy::components::Enemy;
+use crate::events::GameOver;
+use crate::mode::GameMode;
+use crate::player::components::{Player, PlayerId};
+use crate::player::resources::{PlayerIntent, PlayerIntents};
+use crate::score::resources::Score;
+use crate::star::components::Star;
+
+// inputs waiting to be applied before the oldest are skipped, so a client catching up after a
+// stall doesn't stay behind
+const MAX_QUEUED_INPUTS: usize = 8;
+
+// the clients of a server running the game for them
+#[derive(Resource)]
+pub struct NetServer {
+    socket: UdpSocket,
+    clients: Vec<Connection>,
+    // fixed ticks simulated, across rounds
+    tick: u32,
+}
+
+// a client playing one of the players
+#[derive(Debug)]
+struct Connection {
+    addr: SocketAddr,
+    player: PlayerId,
+    // intents by client tick, not applied yet
+    inputs: BTreeMap<u32, PlayerIntent>,
+    // the client tick of the intent applied last, and the intent kept until the next arrives
+    last_input: u32,
+    intent: PlayerIntent,
+    last_heard: Instant,
+}
+
+impl NetServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(NetServer {
+            socket,
+            clients: Vec::new(),
+            tick: 0,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    // the players which have a client
+    pub fn players(&self) -> Vec<PlayerId> {
+        self.clients.iter().map(|client| client.player).collect()
+    }
+
+    fn send(&self, addr: SocketAddr, message: &ServerMessage) {
+        if let Err(error) = self.socket.send_to(&message.to_bytes(), addr) {
+            warn!("Couldn't send to {addr}: {error}");
+        }
+    }
+
+    fn welcome(&mut self, addr: SocketAddr, mode: GameMode, arena: Vec2) {
+        let known = self.clients.iter().find(|client| client.addr == addr).map(|client| client.player);
+        let free = mode
+            .players()
+            .iter()
+            .copied()
+            .find(|&id| self.clients.iter().all(|client| client.player != id));
+
+        let Some(player) = known.or(free) else {
+            self.send(addr, &ServerMessage::Full);
+            return;
+        };
+        if known.is_none() {
+            info!("{addr} joined as player {}", player.number());
+            self.clients.push(Connection {
+                addr,
+                player,
+                inputs: BTreeMap::new(),
+                last_input: 0,
+                intent: PlayerIntent::default(),
+                last_heard: Instant::now(),
+            });
+        }
+        self.send(addr, &ServerMessage::Welcome { player, mode, arena });
+    }
+}
+
+pub fn receive_client_messages(
+    mut server: ResMut<NetServer>,
+    mode: Res<GameMode>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let window = window_query.single().expect("PrimaryWindow not found");
+    let arena = Vec2::new(window.width(), window.height());
+    let mut buffer = [0; DATAGRAM_SIZE];
+
+    loop {
+        let (length, addr) = match server.socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            // e.g. a client which went away, on some platforms
+            Err(error) => {
+                debug!("Couldn't receive from a client: {error}");
+                continue;
+            }
+        };
+        let message = match ClientMessage::from_bytes(&buffer[..length]) {
+            Ok(message) => message,
+            Err(error) => {
+                warn!("Ignoring a datagram from {addr}: {error}");
+                continue;
+            }
+        };
+
+        if let Some(client) = server.clients.iter_mut().find(|client| client.addr == addr) {
+            client.last_heard = Instant::now();
+        }
+        match message {
+            ClientMessage::Hello => server.welcome(addr, *mode, arena),
+            ClientMessage::Inputs { first_tick, intents } => {
+                let Some(client) = server.clients.iter_mut().find(|client| client.addr == addr) else {
+                    continue;
+                };
+                for (tick, intent) in (first_tick..).zip(intents) {
+                    if tick > client.last_input {
+                        client.inputs.insert(tick, intent);
+                    }
+                }
+            }
+            ClientMessage::Bye => {
+                server.clients.retain(|client| {
+                    let leaving = client.addr == addr;
+                    if leaving {
+                        info!("Player {} left", client.player.number());
+                    }
+                    !leaving
+                });
+            }
+        }
+    }
+
+    server.clients.retain(|client| {
+        let timed_out = client.last_heard.elapsed() > Duration::from_secs_f32(CLIENT_TIMEOUT);
+        if timed_out {
+            info!("Player {} timed out", client.player.number());
+        }
+        !timed_out
+    });
+}
+
+// one input of each client per tick, the players without a client stand still
+pub fn apply_client_inputs(mut server: ResMut<NetServer>, mut intents: ResMut<PlayerIntents>) {
+    *intents = PlayerIntents::default();
+    for client in server.clients.iter_mut() {
+        while client.inputs.len() > MAX_QUEUED_INPUTS {
+            client.inputs.pop_first();
+        }
+        // a late input keeps the player going the same way meanwhile
+        if let Some((tick, intent)) = client.inputs.pop_first() {
+            client.last_input = tick;
+            client.intent = intent;
+        }
+        intents.0[client.player.index()] = client.intent;
+    }
+}
+
+pub fn send_snapshots(
+    mut server: ResMut<NetServer>,
+    player_query: Query<(&Transform, &Player)>,
+    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    star_query: Query<(Entity, &Transform), With<Star>>,
+    score: Res<Score>,
+) {
+    server.tick += 1;
+    if server.clients.is_empty() {
+        return;
+    }
+
+    let entities = |(entity, transform): (Entity, &Transform)| (entity.to_bits(), transform.translation.truncate());
+    let mut snapshot = Snapshot {
+        tick: server.tick,
+        acked_input: 0,
+        score: score.clone(),
+        players: player_query
+            .iter()
+            .map(|(transform, player)| PlayerState {
+                id: player.id,
+                lives: player.lives,
+                position: transform.translation.truncate(),
+            })
+            .collect(),
+        enemies: enemy_query.iter().map(entities).collect(),
+        stars: star_query.iter().map(entities).collect(),
+    };
+
+    for client in &server.clients {
+        snapshot.acked_input = client.last_input;
+        server.send(client.addr, &ServerMessage::Snapshot(snapshot.clone()));
+    }
+}
+
+// nobody is left to play the round, start the next one
+pub fn restart_on_game_over(
+    mut game_over_event_reader: EventReader<GameOver>,
+    mut next_state: ResMut<NextState<common::AppState>>,
+) {
+    if game_over_event_reader.read().last().is_some() {
+        info!("Starting the next round");
+        next_state.set(LOADING);
+    }
+}
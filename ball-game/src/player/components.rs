@@ -1,4 +1,45 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
+// how many players a round can have
+pub const PLAYERS: usize = 2;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Player {
+    pub id: PlayerId,
+    pub lives: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum PlayerId {
+    One,
+    Two,
+}
+
+impl PlayerId {
+    pub const ALL: [PlayerId; PLAYERS] = [PlayerId::One, PlayerId::Two];
+
+    // position in the per-player arrays
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    // as shown to the players, from 1
+    pub fn number(self) -> usize {
+        self.index() + 1
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        PlayerId::ALL.get(index).copied()
+    }
+}
+
+// can't be hit again until the timer finishes, after losing a life
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+// can't steal or be stolen from again until the timer finishes, in versus
 #[derive(Component)]
-pub struct Player {}
+pub struct StealCooldown(pub Timer);
@@ -1,18 +1,51 @@
 use bevy::prelude::*;
+use common::{AddSoundExt, Sound};
+
+use crate::{BallGameSet, STATE};
+use crate::assets::BallGameAssets;
+use crate::bot::Bot;
 
 pub mod components;
+pub mod resources;
 mod systems;
 
+use components::{Player, PlayerId};
+use resources::*;
 use systems::*;
 
+pub use systems::step_player;
+
+pub const PLAYER_SIZE: f32 = 64.0;
+pub const EXPLOSION_SOUND: &str = "ball-game/explosion";
+pub const STAR_SOUND: &str = "ball-game/star";
+// player two is tinted to tell them apart
+pub const PLAYER_COLORS: [Color; 2] = [Color::WHITE, Color::srgb(0.5, 0.8, 1.0)];
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_player)
-            .add_systems(Update, player_movement)
-            .add_systems(Update, confine_player_movement)
-            .add_systems(Update, player_hit_enemy)
-            .add_systems(Update, player_hit_star);
+        app.init_resource::<PlayerIntents>()
+            .register_type::<Player>()
+            .add_sound(EXPLOSION_SOUND, Sound::sfx(&["audio/explosionCrunch_001.ogg"]).max_playing(1))
+            .add_sound(STAR_SOUND, Sound::sfx(&["audio/laserLarge_001.ogg"]).max_playing(2))
+            .add_systems(OnEnter(STATE), spawn_players.in_set(BallGameSet::Player))
+            // a bot steers the players instead
+            .add_systems(FixedUpdate, read_player_intents.in_set(BallGameSet::Input).run_if(not(resource_exists::<Bot>)))
+            .add_systems(FixedUpdate, (
+                tick_player_timers,
+                player_movement,
+                confine_player_movement,
+                player_hit_enemy,
+                player_hit_star,
+                players_steal_stars,
+            ).chain().in_set(BallGameSet::Player));
+    }
+}
+
+pub fn player_sprite(id: PlayerId, assets: &BallGameAssets) -> Sprite {
+    Sprite {
+        color: PLAYER_COLORS[id.index()],
+        ..Sprite::from_image(assets.player.clone())
     }
 }
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use super::components::{PLAYERS, PlayerId};
+
+// the movement asked for this tick, quantized so a replay feeds back exactly what was played
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerIntent {
+    pub x: i8,
+    pub y: i8,
+}
+
+impl PlayerIntent {
+    pub fn from_direction(direction: Vec2) -> Self {
+        let quantize = |value: f32| (value.clamp(-1.0, 1.0) * 127.0).round() as i8;
+        PlayerIntent {
+            x: quantize(direction.x),
+            y: quantize(direction.y),
+        }
+    }
+
+    pub fn direction(self) -> Vec2 {
+        Vec2::new(self.x as f32, self.y as f32) / 127.0
+    }
+}
+
+// the intent of every player this tick, including those who aren't playing
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerIntents(pub [PlayerIntent; PLAYERS]);
+
+impl PlayerIntents {
+    pub fn get(&self, id: PlayerId) -> PlayerIntent {
+        self.0[id.index()]
+    }
+}
@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use bevy::window::{Window, PrimaryWindow};
+use action_map::ActionState;
+use common::PlaySoundExt;
 
-use super::components::Player;
+use super::components::{Invulnerable, Player, PlayerId, StealCooldown};
+use super::resources::{PlayerIntent, PlayerIntents};
+use super::{EXPLOSION_SOUND, PLAYER_SIZE, STAR_SOUND, player_sprite};
 
 use crate::enemy::components::Enemy;
 use crate::enemy::{ENEMY_SIZE};
@@ -11,143 +15,289 @@ use crate::star::{STAR_SIZE};
 
 use crate::score::resources::Score;
 
+use crate::STATE;
+use crate::assets::BallGameAssets;
+use crate::actions::GameAction;
+use crate::events::GameOver;
+use crate::mode::GameMode;
+
 // ---------------------------------------------------------------------------
 // Constants
 // ---------------------------------------------------------------------------
 const PLAYER_SPEED: f32 = 250.0;
-const PLAYER_SIZE: f32  = 64.0;
+const STARTING_LIVES: u32 = 3;
+// after losing a life, drawn faded meanwhile
+const INVULNERABLE_SECONDS: f32 = 2.0;
+const INVULNERABLE_ALPHA: f32 = 0.4;
+// between two steals in versus, so a bump doesn't drain every star at once
+const STEAL_COOLDOWN_SECONDS: f32 = 1.0;
+
+// side by side when there are two players, in the middle otherwise
+fn spawn_position(id: PlayerId, mode: GameMode, window: &Window) -> Vec3 {
+    let x = match mode.players().len() {
+        1 => window.width() / 2.0,
+        _ => window.width() * (id.number() as f32) / 3.0,
+    };
+    Vec3::new(x, window.height() / 2.0, 0.0)
+}
 
-pub fn spawn_player(
+pub fn spawn_players(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>
+    assets: Res<BallGameAssets>,
+    mode: Res<GameMode>,
     ) {
 
     // there should only be one instance of window that is labeled
     // primary window as long as bevy is running.
     let window = window_query.single().expect("Primary Window not found?");
 
+    for &id in mode.players() {
+        commands.spawn((
+            player_sprite(id, &assets),
+            Transform::from_translation(spawn_position(id, *mode, window)),
+            Player { id, lives: STARTING_LIVES },
+            StateScoped(STATE),
+        ));
+    }
+}
+
+// the strongest input of the movement actions of `ids`
+fn move_direction(actions: &ActionState<GameAction>, ids: &[PlayerId]) -> Vec2 {
+    let value = |pick: fn([GameAction; 4]) -> GameAction| {
+        ids.iter()
+            .map(|&id| actions.value(pick(GameAction::moves(id))))
+            .fold(0.0, f32::max)
+    };
+    // analog sticks give values between 0 and 1, so the player can move slower than full speed
+    let mut direction = Vec2::new(
+        value(|[_, right, _, _]| right) - value(|[left, _, _, _]| left),
+        value(|[_, _, up, _]| up) - value(|[_, _, _, down]| down),
+    );
 
-    commands.spawn((
-        // Create a Sprite from an image handle
-        Sprite::from_image(asset_server.load("sprites/ball_blue_large.png")),
-        // Position at the window's center
-        Transform::from_xyz(
-            window.width() / 2.0,
-            window.height() / 2.0,
-            0.0,
-        ),
-        // Your marker component
-        Player{},
-    ));
+    // don't let diagonal movement be faster than straight movement
+    if direction.length() > 1.0 {
+        direction = direction.normalize();
+    }
+    direction
 }
 
-pub fn player_movement(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<&mut Transform, With<Player>>,
-    time: Res<Time>,
+// read once per fixed tick, so live play and replays move the players the same way
+pub fn read_player_intents(
+    actions: Res<ActionState<GameAction>>,
+    mode: Res<GameMode>,
+    mut intents: ResMut<PlayerIntents>,
 ) {
-    // `single_mut()` returns a Result; unwrap or handle errors as needed
-    if let Ok(mut transform) = player_query.single_mut() {
-        let mut direction = Vec3::ZERO;
-
-        if keyboard_input.pressed(KeyCode::ArrowLeft) || keyboard_input.pressed(KeyCode::KeyA) {
-            direction.x -= 1.0;
+    *intents = PlayerIntents::default();
+    match *mode {
+        // alone, both sets of controls work
+        GameMode::Solo => {
+            intents.0[PlayerId::One.index()] = PlayerIntent::from_direction(move_direction(&actions, &PlayerId::ALL));
         }
-        if keyboard_input.pressed(KeyCode::ArrowRight) || keyboard_input.pressed(KeyCode::KeyD) {
-            direction.x += 1.0;
+        GameMode::Coop | GameMode::Versus => {
+            for id in PlayerId::ALL {
+                intents.0[id.index()] = PlayerIntent::from_direction(move_direction(&actions, &[id]));
+            }
         }
-        if keyboard_input.pressed(KeyCode::ArrowUp) || keyboard_input.pressed(KeyCode::KeyW) {
-            direction.y += 1.0;
+    }
+}
+
+pub fn tick_player_timers(
+    mut commands: Commands,
+    mut invulnerable_query: Query<(Entity, &mut Invulnerable, &mut Sprite)>,
+    mut cooldown_query: Query<(Entity, &mut StealCooldown)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable, mut sprite) in invulnerable_query.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            sprite.color.set_alpha(1.0);
+            commands.entity(entity).remove::<Invulnerable>();
         }
-        if keyboard_input.pressed(KeyCode::ArrowDown) || keyboard_input.pressed(KeyCode::KeyS) {
-            direction.y -= 1.0;
+    }
+    for (entity, mut cooldown) in cooldown_query.iter_mut() {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<StealCooldown>();
         }
+    }
+}
 
-        if direction.length() > 0.0 {
-            direction = direction.normalize();
-        }
+// how far `intent` moves a player in `delta_secs`
+fn movement(intent: PlayerIntent, delta_secs: f32) -> Vec3 {
+    let direction = intent.direction().clamp_length_max(1.0).extend(0.0);
+    direction * PLAYER_SPEED * delta_secs
+}
+
+// `translation` kept inside an arena of `size`
+fn confined(mut translation: Vec3, size: Vec2) -> Vec3 {
+    // Compute the allowed min/max positions
+    let half = PLAYER_SIZE / 2.0;
+    translation.x = translation.x.clamp(half, size.x - half);
+    translation.y = translation.y.clamp(half, size.y - half);
+    translation
+}
 
+// one fixed tick of movement, the same as the player systems do, for predicting it elsewhere
+pub fn step_player(translation: Vec3, intent: PlayerIntent, delta_secs: f32, arena: Vec2) -> Vec3 {
+    confined(translation + movement(intent, delta_secs), arena)
+}
+
+pub fn player_movement(
+    intents: Res<PlayerIntents>,
+    mut player_query: Query<(&mut Transform, &Player)>,
+    time: Res<Time>,
+) {
+    for (mut transform, player) in player_query.iter_mut() {
         // Use `delta_secs()` instead of the removed `delta_seconds()`
-        transform.translation += direction * PLAYER_SPEED * time.delta_secs();
+        transform.translation += movement(intents.get(player.id), time.delta_secs());
     }
 }
 
 pub fn confine_player_movement(
-    mut query: Query<&mut Transform, With<Player>>,   // Query::single_mut() for exclusive mut access :contentReference[oaicite:6]{index=6}
+    mut query: Query<&mut Transform, With<Player>>,
     window_query: Query<&Window, With<PrimaryWindow>>, // Query the primary window for dimensions :contentReference[oaicite:7]{index=7}
 ) {
-    if let Ok(mut transform) = query.single_mut() {
-        let window = window_query
-            .single()
-            .expect("PrimaryWindow not found");
-
-        // Compute the allowed min/max positions
-        let half = PLAYER_SIZE / 2.0;
-        let x_min = half;
-        let x_max = window.width()  - half;
-        let y_min = half;
-        let y_max = window.height() - half;
+    let window = window_query
+        .single()
+        .expect("PrimaryWindow not found");
+    let size = Vec2::new(window.width(), window.height());
 
+    for mut transform in query.iter_mut() {
         // Clamp the translation to stay within the window
-        let mut pos = transform.translation;
-        pos.x = pos.x.clamp(x_min, x_max);
-        pos.y = pos.y.clamp(y_min, y_max);
-
-        transform.translation = pos;
+        transform.translation = confined(transform.translation, size);
     }
 }
 
+// a hit costs a life and sends the player back to the start, the round is over once every
+// player is out of lives
+#[allow(clippy::type_complexity)]
 pub fn player_hit_enemy(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &Transform), With<Player>>,
-    enemy_query: Query<&Transform, With<Enemy>>,
-    asset_server: Res<AssetServer>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Player, &mut Sprite), Without<Invulnerable>>,
+    all_players_query: Query<(), With<Player>>,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
 ) {
-    if let Ok((player_entity, player_transform)) = player_query.single_mut() {
-        for enemy_transform in enemy_query.iter() {
+    let window = window_query.single().expect("PrimaryWindow not found");
+    let mut players_out = 0;
+
+    for (player_entity, mut player_transform, mut player, mut sprite) in player_query.iter_mut() {
+        let hit = enemy_query.iter().any(|enemy_transform| {
             let distance = player_transform
                 .translation
                 .distance(enemy_transform.translation);
             let player_radius = PLAYER_SIZE / 2.0;
             let enemy_radius = ENEMY_SIZE / 2.0;
-            if distance < player_radius + enemy_radius {
-                println!("Enemy hit player! Game Over!");
-                let sound_effect = asset_server.load("audio/explosionCrunch_001.ogg");
-                // Spawn an entity to play the sound once
-                commands.spawn((
-                    AudioPlayer::new(sound_effect),
-                    PlaybackSettings::ONCE,
-                ));
-                commands.entity(player_entity).despawn();
-            }
+            distance < player_radius + enemy_radius
+        });
+        if !hit {
+            continue;
+        }
+
+        commands.play_sound(EXPLOSION_SOUND);
+        player.lives = player.lives.saturating_sub(1);
+        if player.lives == 0 {
+            info!("Enemy hit player {}! They are out!", player.id.number());
+            commands.entity(player_entity).despawn();
+            players_out += 1;
+        } else {
+            info!("Enemy hit player {}! {} lives left", player.id.number(), player.lives);
+            player_transform.translation = spawn_position(player.id, *mode, window);
+            sprite.color.set_alpha(INVULNERABLE_ALPHA);
+            commands.entity(player_entity).insert(Invulnerable(Timer::from_seconds(INVULNERABLE_SECONDS, TimerMode::Once)));
+        }
+    }
+
+    if players_out > 0 && players_out == all_players_query.iter().count() {
+        info!("Game Over!");
+        commands.send_event(game_over(*mode, &score));
+    }
+}
+
+// solo and co-op score together, versus is won by the most stars
+fn game_over(mode: GameMode, score: &Score) -> GameOver {
+    match mode {
+        GameMode::Solo | GameMode::Coop => GameOver { score: score.value, winner: None },
+        GameMode::Versus => {
+            let [one, two] = score.players;
+            let winner = match one.cmp(&two) {
+                std::cmp::Ordering::Greater => Some(PlayerId::One),
+                std::cmp::Ordering::Less => Some(PlayerId::Two),
+                std::cmp::Ordering::Equal => None,
+            };
+            GameOver { score: one.max(two), winner }
         }
     }
 }
 
 pub fn player_hit_star(
     mut commands: Commands,
-    player_query: Query<&Transform, With<Player>>,
+    player_query: Query<(&Transform, &Player)>,
     star_query: Query<(Entity, &Transform), With<Star>>,
-    asset_server: Res<AssetServer>,
     mut score: ResMut<Score>,
 ) {
-    if let Ok(player_transform) = player_query.single() {
-        for (star_entity, star_transform) in star_query.iter() {
+    // player one first when both touch the same star
+    let mut players: Vec<(&Transform, &Player)> = player_query.iter().collect();
+    players.sort_by_key(|(_, player)| player.id);
+
+    for (star_entity, star_transform) in star_query.iter() {
+        let collector = players.iter().find(|(player_transform, _)| {
             let distance = player_transform
                 .translation
                 .distance(star_transform.translation);
+            distance < PLAYER_SIZE / 2.0 + STAR_SIZE / 2.0
+        });
 
-            if distance < PLAYER_SIZE / 2.0 + STAR_SIZE / 2.0 {
-                info!("Player hit star!");
-                score.value += 1;
-                let sound_effect = asset_server.load("audio/laserLarge_001.ogg");
-                commands.spawn((
-                    AudioPlayer::new(sound_effect),
-                    PlaybackSettings::ONCE,
-                ));
-                commands.entity(star_entity).despawn();
-            }
+        if let Some((_, player)) = collector {
+            info!("Player {} hit star!", player.id.number());
+            score.value += 1;
+            score.players[player.id.index()] += 1;
+            commands.play_sound(STAR_SOUND);
+            commands.entity(star_entity).despawn();
         }
     }
 }
+
+// in versus, the player bumping into the other steals one of their stars
+pub fn players_steal_stars(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform, &Player), Without<StealCooldown>>,
+    intents: Res<PlayerIntents>,
+    mode: Res<GameMode>,
+    mut score: ResMut<Score>,
+) {
+    if *mode != GameMode::Versus {
+        return;
+    }
+    let mut players: Vec<(Entity, &Transform, &Player)> = player_query.iter().collect();
+    players.sort_by_key(|(_, _, player)| player.id);
+    let [(one_entity, one_transform, one), (two_entity, two_transform, two)] = players[..] else {
+        return;
+    };
+
+    let offset = two_transform.translation.truncate() - one_transform.translation.truncate();
+    if offset.length() >= PLAYER_SIZE {
+        return;
+    }
+
+    // whoever pushes harder towards the other is the one bumping
+    let towards_two = offset.normalize_or_zero();
+    let one_push = intents.get(one.id).direction().dot(towards_two);
+    let two_push = intents.get(two.id).direction().dot(-towards_two);
+    let (thief, victim) = match one_push.total_cmp(&two_push) {
+        std::cmp::Ordering::Greater => (one.id, two.id),
+        std::cmp::Ordering::Less => (two.id, one.id),
+        std::cmp::Ordering::Equal => return,
+    };
+
+    if score.players[victim.index()] > 0 {
+        info!("Player {} stole a star from player {}!", thief.number(), victim.number());
+        score.players[victim.index()] -= 1;
+        score.players[thief.index()] += 1;
+        commands.play_sound(STAR_SOUND);
+    }
+    for entity in [one_entity, two_entity] {
+        commands.entity(entity).insert(StealCooldown(Timer::from_seconds(STEAL_COOLDOWN_SECONDS, TimerMode::Once)));
+    }
+}
@@ -0,0 +1,37 @@
+// recording every run and playing one back instead of the inputs
+//
+// a replay is the seed and mode of the run and the players' intents for every fixed tick, since the
+// rest of the game follows from those. every few ticks a checksum of the world is stored too,
+// so a playback that drifts from the recording is noticed. replays only play back the same on
+// builds doing the same float math, e.g. the same binary.
+
+use bevy::prelude::*;
+
+use crate::{BallGameSet, STATE};
+use crate::attract::demo_playing;
+
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+// ticks between two checksums of the world
+pub const CHECKSUM_INTERVAL: u32 = 60;
+// every run is recorded here, next to where the game is run
+pub const REPLAY_PATH: &str = "replays/last.replay";
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // the demo isn't recorded
+        app.init_resource::<ReplayMode>()
+            .init_resource::<ReplayRun>()
+            .add_systems(OnEnter(STATE), start_run.in_set(BallGameSet::Replay).run_if(not(demo_playing)))
+            .add_systems(OnExit(STATE), save_unfinished_recording.run_if(not(demo_playing)))
+            .add_systems(FixedUpdate, feed_intents.in_set(BallGameSet::Replay).run_if(not(demo_playing)))
+            .add_systems(FixedUpdate, check_world.in_set(BallGameSet::Checksum).run_if(not(demo_playing)))
+            .add_systems(Update, finish_on_game_over.run_if(in_state(STATE).and(not(demo_playing))));
+    }
+}
@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::CHECKSUM_INTERVAL;
+use crate::mode::GameMode;
+use crate::player::resources::{PlayerIntent, PlayerIntents};
+
+// start of every replay file, followed by the format version
+const MAGIC: &[u8; 4] = b"BGRP";
+const VERSION: u8 = 2;
+
+// a recorded run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    // window size of the recording, the spawns depend on it
+    pub arena: Vec2,
+    pub checksum_interval: u32,
+    pub mode: GameMode,
+    // the players' intents for every fixed tick
+    pub intents: Vec<PlayerIntents>,
+    // the world checksum after every `checksum_interval` ticks
+    pub checksums: Vec<u64>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, arena: Vec2, mode: GameMode) -> Self {
+        Replay {
+            seed,
+            arena,
+            checksum_interval: CHECKSUM_INTERVAL,
+            mode,
+            intents: Vec::new(),
+            checksums: Vec::new(),
+        }
+    }
+
+    // little endian: the header, the intents as runs of the same intent, then the checksums
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.arena.x.to_le_bytes());
+        bytes.extend_from_slice(&self.arena.y.to_le_bytes());
+        bytes.extend_from_slice(&self.checksum_interval.to_le_bytes());
+        bytes.push(self.mode.to_byte());
+
+        // the intents mostly stay the same for many ticks
+        let mut runs: Vec<(u16, PlayerIntents)> = Vec::new();
+        for &intent in &self.intents {
+            match runs.last_mut() {
+                Some((length, last)) if *last == intent && *length < u16::MAX => *length += 1,
+                _ => runs.push((1, intent)),
+            }
+        }
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (length, intents) in runs {
+            bytes.extend_from_slice(&length.to_le_bytes());
+            for intent in intents.0 {
+                bytes.extend_from_slice(&intent.x.to_le_bytes());
+                bytes.extend_from_slice(&intent.y.to_le_bytes());
+            }
+        }
+
+        bytes.extend_from_slice(&(self.checksums.len() as u32).to_le_bytes());
+        for checksum in &self.checksums {
+            bytes.extend_from_slice(&checksum.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader(bytes);
+        if reader.take::<4>()? != *MAGIC {
+            return Err("not a ball-game replay".to_string());
+        }
+        let [version] = reader.take::<1>()?;
+        if version != VERSION {
+            return Err(format!("unsupported replay version {version}"));
+        }
+        let seed = u64::from_le_bytes(reader.take()?);
+        let arena = Vec2::new(
+            f32::from_le_bytes(reader.take()?),
+            f32::from_le_bytes(reader.take()?),
+        );
+        let checksum_interval = u32::from_le_bytes(reader.take()?);
+        if checksum_interval == 0 {
+            return Err("invalid checksum interval 0".to_string());
+        }
+        let [mode] = reader.take::<1>()?;
+        let Some(mode) = GameMode::from_byte(mode) else {
+            return Err(format!("unknown game mode {mode}"));
+        };
+
+        let mut intents = Vec::new();
+        for _ in 0..u32::from_le_bytes(reader.take()?) {
+            let length = u16::from_le_bytes(reader.take()?);
+            let mut run = PlayerIntents::default();
+            for intent in &mut run.0 {
+                *intent = PlayerIntent {
+                    x: i8::from_le_bytes(reader.take()?),
+                    y: i8::from_le_bytes(reader.take()?),
+                };
+            }
+            intents.extend(std::iter::repeat_n(run, length as usize));
+        }
+
+        let mut checksums = Vec::new();
+        for _ in 0..u32::from_le_bytes(reader.take()?) {
+            checksums.push(u64::from_le_bytes(reader.take()?));
+        }
+
+        if !reader.0.is_empty() {
+            return Err(format!("{} unexpected bytes at the end", reader.0.len()));
+        }
+        Ok(Replay {
+            seed,
+            arena,
+            checksum_interval,
+            mode,
+            intents,
+            checksums,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|error| format!("couldn't read {}: {error}", path.display()))?;
+        Self::from_bytes(&bytes).map_err(|error| format!("invalid replay {}: {error}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        fs::write(path, self.to_bytes())
+    }
+}
+
+// the bytes of a replay file left to read
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let Some((bytes, rest)) = self.0.split_first_chunk::<N>() else {
+            return Err("the replay is cut short".to_string());
+        };
+        self.0 = rest;
+        Ok(*bytes)
+    }
+}
+
+// what happens to the runs
+#[derive(Resource, Debug, Clone, Default)]
+pub enum ReplayMode {
+    // neither recorded nor played back, the benchmark seeds the rng itself
+    Off,
+    // every run is saved to REPLAY_PATH when it ends
+    #[default]
+    Record,
+    // the runs play this replay instead of the inputs
+    Playback(Replay),
+}
+
+// the replay of the current run, being recorded or played back
+#[derive(Resource, Debug)]
+pub struct ReplayRun {
+    pub replay: Replay,
+    // fixed ticks played in this run
+    pub tick: usize,
+    // saved, or played back to its end
+    pub finished: bool,
+    // warned about the first checksum which didn't match, the rest would follow from it
+    pub desynced: bool,
+}
+
+impl Default for ReplayRun {
+    fn default() -> Self {
+        ReplayRun {
+            replay: Replay::new(0, Vec2::ZERO, GameMode::Solo),
+            tick: 0,
+            finished: false,
+            desynced: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        let mut replay = Replay::new(42, Vec2::new(1280.0, 720.0), GameMode::Versus);
+        let still = PlayerIntents::default();
+        let moving = PlayerIntents([PlayerIntent { x: 1, y: -1 }, PlayerIntent { x: -1, y: 0 }]);
+        // long enough for a run to be split at u16::MAX
+        replay.intents.extend(std::iter::repeat_n(still, 3));
+        replay.intents.extend(std::iter::repeat_n(moving, u16::MAX as usize + 5));
+        replay.intents.push(still);
+        replay.checksums = vec![1, u64::MAX, 0];
+        replay
+    }
+
+    #[test]
+    fn replays_round_trip() {
+        let replay = replay();
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay));
+
+        let empty = Replay::new(0, Vec2::ZERO, GameMode::Solo);
+        assert_eq!(Replay::from_bytes(&empty.to_bytes()), Ok(empty));
+    }
+
+    #[test]
+    fn corrupt_replays_are_rejected() {
+        let bytes = replay().to_bytes();
+        let with = |index: usize, byte: u8| {
+            let mut bytes = bytes.clone();
+            bytes[index] = byte;
+            bytes
+        };
+        // magic, version, seed, arena, then the checksum interval and the mode
+        let interval = 4 + 1 + 8 + 8;
+        let mut zero_interval = bytes.clone();
+        zero_interval[interval..interval + 4].fill(0);
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+
+        let cases = [
+            (with(0, b'X'), "not a ball-game replay".to_string()),
+            (with(4, VERSION + 1), format!("unsupported replay version {}", VERSION + 1)),
+            (zero_interval, "invalid checksum interval 0".to_string()),
+            (with(interval + 4, 9), "unknown game mode 9".to_string()),
+            (bytes[..bytes.len() - 1].to_vec(), "the replay is cut short".to_string()),
+            (Vec::new(), "the replay is cut short".to_string()),
+            (trailing, "1 unexpected bytes at the end".to_string()),
+        ];
+        for (corrupt, error) in cases {
+            assert_eq!(Replay::from_bytes(&corrupt), Err(error));
+        }
+    }
+}
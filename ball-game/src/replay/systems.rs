@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use bevy::window::{Window, PrimaryWindow};
+use rand::Rng;
+use std::path::Path;
+
+use super::REPLAY_PATH;
+use super::resources::*;
+use crate::enemy::components::Enemy;
+use crate::events::GameOver;
+use crate::mode::GameMode;
+use crate::player::components::Player;
+use crate::player::resources::PlayerIntents;
+use crate::rng::GameRng;
+use crate::score::resources::Score;
+use crate::star::components::Star;
+
+// seed the run before anything is spawned
+pub fn start_run(
+    mode: Res<ReplayMode>,
+    mut run: ResMut<ReplayRun>,
+    mut rng: ResMut<GameRng>,
+    mut game_mode: ResMut<GameMode>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let window = window_query.single().unwrap();
+    let arena = Vec2::new(window.width(), window.height());
+
+    let replay = match &*mode {
+        ReplayMode::Off => return,
+        // a fresh seed for every run, kept so the run can be played again
+        ReplayMode::Record => Replay::new(rng.random::<u64>(), arena, *game_mode),
+        ReplayMode::Playback(replay) => {
+            if replay.arena != arena {
+                warn!(
+                    "The replay was recorded in a {}x{} window, not {}x{}, it won't play the same",
+                    replay.arena.x, replay.arena.y, arena.x, arena.y,
+                );
+            }
+            info!("Playing a {} replay of {} ticks", replay.mode.name(), replay.intents.len());
+            // the players are spawned for the recorded mode
+            *game_mode = replay.mode;
+            replay.clone()
+        }
+    };
+    *rng = GameRng::seeded(replay.seed);
+    *run = ReplayRun {
+        replay,
+        ..default()
+    };
+}
+
+pub fn feed_intents(
+    mode: Res<ReplayMode>,
+    mut run: ResMut<ReplayRun>,
+    mut intents: ResMut<PlayerIntents>,
+) {
+    if run.finished {
+        return;
+    }
+    match &*mode {
+        ReplayMode::Off => return,
+        ReplayMode::Record => run.replay.intents.push(*intents),
+        ReplayMode::Playback(_) => match run.replay.intents.get(run.tick) {
+            Some(&recorded) => *intents = recorded,
+            None => {
+                info!("The replay is over after {} ticks, the controls are back", run.tick);
+                run.finished = true;
+                return;
+            }
+        },
+    }
+    run.tick += 1;
+}
+
+pub fn check_world(
+    mode: Res<ReplayMode>,
+    mut run: ResMut<ReplayRun>,
+    player_query: Query<(&Transform, &Player)>,
+    enemy_query: Query<(&Transform, &Enemy)>,
+    star_query: Query<&Transform, With<Star>>,
+    score: Res<Score>,
+) {
+    let interval = run.replay.checksum_interval as usize;
+    if matches!(*mode, ReplayMode::Off) || run.finished || run.tick == 0 || !run.tick.is_multiple_of(interval) {
+        return;
+    }
+    let checksum = world_checksum(&player_query, &enemy_query, &star_query, &score);
+
+    if matches!(*mode, ReplayMode::Record) {
+        run.replay.checksums.push(checksum);
+        return;
+    }
+    let expected = run.replay.checksums.get(run.tick / interval - 1).copied();
+    if let Some(expected) = expected
+        && expected != checksum
+        && !run.desynced
+    {
+        warn!(
+            "The replay desynced between ticks {} and {}",
+            run.tick - interval,
+            run.tick
+        );
+        run.desynced = true;
+    }
+}
+
+pub fn finish_on_game_over(
+    mut game_over_event_reader: EventReader<GameOver>,
+    mode: Res<ReplayMode>,
+    mut run: ResMut<ReplayRun>,
+) {
+    if game_over_event_reader.read().last().is_none() {
+        return;
+    }
+    match &*mode {
+        ReplayMode::Off => {}
+        ReplayMode::Record => save_recording(&mut run),
+        ReplayMode::Playback(_) => {
+            if !run.desynced {
+                info!("The replay played back without desyncing");
+            }
+        }
+    }
+}
+
+// leaving the game before the game over still keeps the run
+pub fn save_unfinished_recording(mode: Res<ReplayMode>, mut run: ResMut<ReplayRun>) {
+    if matches!(*mode, ReplayMode::Record) {
+        save_recording(&mut run);
+    }
+}
+
+fn save_recording(run: &mut ReplayRun) {
+    if run.finished {
+        return;
+    }
+    run.finished = true;
+    match run.replay.save(Path::new(REPLAY_PATH)) {
+        Ok(()) => info!("Saved the replay of {} ticks to {REPLAY_PATH}", run.tick),
+        Err(error) => warn!("Couldn't save the replay to {REPLAY_PATH}: {error}"),
+    }
+}
+
+// FNV-1a over everything the replay should reproduce, independent of the query order
+fn world_checksum(
+    player_query: &Query<(&Transform, &Player)>,
+    enemy_query: &Query<(&Transform, &Enemy)>,
+    star_query: &Query<&Transform, With<Star>>,
+    score: &Score,
+) -> u64 {
+    let bits = |values: &[f32]| values.iter().map(|value| value.to_bits()).collect::<Vec<u32>>();
+
+    let mut players: Vec<Vec<u32>> = player_query
+        .iter()
+        .map(|(t, player)| {
+            let mut values = vec![player.id.index() as u32, player.lives];
+            values.extend(bits(&t.translation.to_array()));
+            values
+        })
+        .collect();
+    let mut enemies: Vec<Vec<u32>> = enemy_query
+        .iter()
+        .map(|(t, enemy)| bits(&[t.translation.x, t.translation.y, enemy.direction.x, enemy.direction.y]))
+        .collect();
+    let mut stars: Vec<Vec<u32>> = star_query.iter().map(|t| bits(&t.translation.to_array())).collect();
+    players.sort_unstable();
+    enemies.sort_unstable();
+    stars.sort_unstable();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut write = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    for group in [players, enemies, stars] {
+        // the counts keep entities from being mistaken for one another
+        write(group.len() as u32);
+        group.iter().flatten().for_each(|&value| write(value));
+    }
+    write(score.value);
+    score.players.iter().for_each(|&value| write(value));
+    hash
+}
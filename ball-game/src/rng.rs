@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+// every random choice that changes the game goes through this, so a seeded run can be repeated.
+// it counts the words it has drawn, which with the seed is enough to save and restore it.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    words: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        GameRng {
+            seed,
+            words: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn state(&self) -> RngState {
+        RngState {
+            seed: self.seed,
+            words: self.words,
+        }
+    }
+
+    // the rng where `state` was taken, by drawing the same words again
+    pub fn restore(state: RngState) -> Self {
+        let mut rng = GameRng::seeded(state.seed);
+        for _ in 0..state.words {
+            rng.next_u32();
+        }
+        rng
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng::seeded(rand::random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.words += 1;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.words += 2;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.words += dest.len().div_ceil(4) as u64;
+        self.rng.fill_bytes(dest);
+    }
+}
+
+// the state of the GameRng in a saved round
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct RngState {
+    pub seed: u64,
+    pub words: u64,
+}
@@ -0,0 +1,73 @@
+// saving the round when leaving it, and continuing it later
+//
+// the round is saved as a scene of the players, enemies and stars with the resources the game
+// depends on, through bevy reflection. continuing restores them after the new round is
+// spawned, replacing it. a round which ended has nothing to continue, so its save is removed.
+// the scenes exported with F6 hold the same round, with the high scores.
+
+use bevy::prelude::*;
+use common::{AddSceneExt, SceneTypes};
+
+use crate::actions::GameAction;
+use crate::attract::demo_playing;
+use crate::enemy::components::Enemy;
+use crate::enemy::resources::EnemySpawnTimer;
+use crate::mode::GameMode;
+use crate::net::client::NetClient;
+use crate::player::components::Player;
+use crate::rng::RngState;
+use crate::score::resources::{HighScores, Score};
+use crate::star::components::Star;
+use crate::star::resources::StarSpawnTimer;
+use crate::{BallGameSet, NAME, STATE};
+
+mod systems;
+
+use systems::*;
+
+// the round in progress, next to where the game is run
+pub const SAVE_PATH: &str = "saves/ball-game.scn.ron";
+
+// everything a round is restored from, the sprites are added back from the assets
+pub fn round_types() -> SceneTypes {
+    SceneTypes::default()
+        .entity::<Player>()
+        .entity::<Enemy>()
+        .entity::<Star>()
+        .component::<Transform>()
+        .resource::<Score>()
+        .resource::<EnemySpawnTimer>()
+        .resource::<StarSpawnTimer>()
+        .resource::<RngState>()
+        .resource::<GameMode>()
+}
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RngState>()
+            .register_type::<RngState>()
+            .add_scene_export(
+                NAME,
+                round_types().resource::<HighScores>(),
+                GameAction::ExportScene,
+                GameAction::ImportScene,
+            )
+            .add_systems(OnEnter(STATE), (
+                continue_round.run_if(continuing),
+                sync_rng_state,
+            ).chain().after(BallGameSet::Star))
+            .add_systems(FixedUpdate, sync_rng_state.after(BallGameSet::Checksum).run_if(in_state(STATE)))
+            .add_systems(Update, (
+                restore_imported_round,
+                // a demo round ending leaves the saved round alone
+                remove_save_on_game_over.run_if(not(demo_playing)),
+            ).run_if(in_state(STATE)))
+            // after everything else, in the frame the game is left or the window closed. a
+            // client's round is the server's, and a demo round nobody's
+            .add_systems(Last, save_round.run_if(
+                in_state(STATE).and(leaving_round).and(not(resource_exists::<NetClient>)).and(not(demo_playing))
+            ));
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use action_map::ActionState;
+use common::scene::{load_scene, save_scene};
+use common::{AppState, ContinueGame, SceneImported};
+use std::fs;
+use std::path::Path;
+
+use super::{SAVE_PATH, round_types};
+use crate::NAME;
+use crate::actions::GameAction;
+use crate::assets::BallGameAssets;
+use crate::enemy::components::Enemy;
+use crate::events::GameOver;
+use crate::player::components::Player;
+use crate::player::player_sprite;
+use crate::replay::resources::ReplayRun;
+use crate::rng::{GameRng, RngState};
+
+pub fn continuing(continue_game: Option<Res<ContinueGame>>) -> bool {
+    continue_game.is_some_and(|continue_game| continue_game.0 == NAME)
+}
+
+// leaving the game, or closing the window, while a player is still alive
+pub fn leaving_round(
+    actions: Res<ActionState<GameAction>>,
+    app_exit_events: EventReader<AppExit>,
+    player_query: Query<(), With<Player>>,
+) -> bool {
+    (actions.just_pressed(GameAction::Exit) || !app_exit_events.is_empty()) && !player_query.is_empty()
+}
+
+// the rng can't be reflected, so the scenes keep its state instead. a state which changed
+// since it was last synced comes from a scene, and restores the rng.
+pub fn sync_rng_state(
+    mut rng: ResMut<GameRng>,
+    mut rng_state: ResMut<RngState>,
+    mut synced: Local<RngState>,
+) {
+    if *rng_state != *synced {
+        *rng = GameRng::restore(*rng_state);
+    }
+    *rng_state = rng.state();
+    *synced = *rng_state;
+}
+
+pub fn save_round(world: &mut World) {
+    let scene = round_types().extract(world);
+    match save_scene(world, &scene, Path::new(SAVE_PATH)) {
+        Ok(()) => info!("Saved the round to {SAVE_PATH}"),
+        Err(error) => warn!("Couldn't save the round to {SAVE_PATH}: {error}"),
+    }
+}
+
+// replace the round which was just spawned by the saved one
+pub fn continue_round(world: &mut World) {
+    world.remove_resource::<ContinueGame>();
+    let continued = load_scene(world, Path::new(SAVE_PATH))
+        .and_then(|scene| round_types().replace_with(world, &scene, AppState::Playing(NAME)));
+    match continued {
+        Ok(entities) => {
+            info!("Continuing the round saved in {SAVE_PATH}");
+            world.send_event(SceneImported { game: NAME, entities });
+        }
+        Err(error) => warn!("Couldn't continue the round saved in {SAVE_PATH}, starting a new one: {error}"),
+    }
+}
+
+// add back what the scenes leave out
+pub fn restore_imported_round(
+    mut commands: Commands,
+    mut scene_imported_reader: EventReader<SceneImported>,
+    assets: Res<BallGameAssets>,
+    kind_query: Query<(Option<&Player>, Has<Enemy>)>,
+    mut run: ResMut<ReplayRun>,
+) {
+    for event in scene_imported_reader.read().filter(|event| event.game == NAME) {
+        for &entity in &event.entities {
+            let Ok((player, is_enemy)) = kind_query.get(entity) else {
+                continue;
+            };
+            let sprite = if let Some(player) = player {
+                player_sprite(player.id, &assets)
+            } else if is_enemy {
+                Sprite::from_image(assets.enemy.clone())
+            } else {
+                Sprite::from_image(assets.star.clone())
+            };
+            commands.entity(entity).insert(sprite);
+        }
+
+        // the replay would start from the seed of a new round, not from the imported one
+        run.finished = true;
+    }
+}
+
+// the round is over, there is nothing to continue
+pub fn remove_save_on_game_over(mut game_over_event_reader: EventReader<GameOver>) {
+    if game_over_event_reader.read().last().is_some() && Path::new(SAVE_PATH).exists()
+        && let Err(error) = fs::remove_file(SAVE_PATH)
+    {
+        warn!("Couldn't remove the finished round saved in {SAVE_PATH}: {error}");
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+use crate::{BallGameSet, STATE};
+use crate::bot::Bot;
+
 pub mod resources;
 mod systems;
 
@@ -11,9 +14,17 @@ pub struct ScorePlugin;
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Score>()
+            .register_type::<Score>()
+            .register_type::<HighScores>()
             .init_resource::<HighScores>()
-            .add_systems(Update, update_score)
-            .add_systems(Update, update_high_scores)
-            .add_systems(Update, high_scores_updated);
+            .init_resource::<HighScoresFile>()
+            .add_systems(Startup, load_high_scores)
+            .add_systems(OnEnter(STATE), reset_score.before(BallGameSet::Player))
+            .add_systems(Update, (
+                update_score,
+                // the scores of a bot, like the demo's, aren't anyone's
+                update_high_scores.run_if(not(resource_exists::<Bot>)),
+                (high_scores_updated, save_high_scores),
+            ).run_if(in_state(STATE)));
     }
 }
@@ -1,23 +1,90 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
 
-#[derive(Resource)]
+use crate::player::components::PLAYERS;
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub struct Score {
+    // every star collected in the round, the team's score in co-op
     pub value: u32,
+    // the stars of each player
+    pub players: [u32; PLAYERS],
 }
 
-impl Default for Score {
-    fn default() -> Score {
-        Score { value: 0 }
+// the high scores and the best run, next to where the game is run, so the ghost of the best run
+// is still there to race after a restart
+pub const HIGH_SCORES_PATH: &str = "scores/ball-game.ron";
+
+// where the high scores are loaded from and saved to, nowhere for the headless runs
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HighScoresFile(pub Option<&'static str>);
+
+impl Default for HighScoresFile {
+    fn default() -> Self {
+        HighScoresFile(Some(HIGH_SCORES_PATH))
     }
 }
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
 pub struct HighScores {
     pub scores: Vec<(String, u32)>,
+    // the run with the top score, raced by its ghost
+    pub best_run: Option<BestRun>,
 }
 
-impl Default for HighScores {
-    fn default() -> HighScores {
-        HighScores { scores: Vec::new() }
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct BestRun {
+    pub score: u32,
+    // where the player was after every fixed tick
+    pub trajectory: Vec<Vec2>,
+}
+
+impl HighScores {
+    // the high scores saved at `path`, or none if there aren't any
+    pub fn load_or_default(path: &Path) -> Self {
+        let Ok(saved) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&saved).unwrap_or_else(|error| {
+            warn!("Ignoring invalid high scores in {}: {error}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        fs::write(path, ron::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_scores_round_trip() {
+        let folder = std::env::temp_dir().join(format!("ball-game-scores-{}", std::process::id()));
+        let path = folder.join("scores.ron");
+        assert_eq!(HighScores::load_or_default(&path), HighScores::default());
+
+        let high_scores = HighScores {
+            scores: vec![("Player".to_string(), 12), ("Team".to_string(), 3)],
+            best_run: Some(BestRun { score: 12, trajectory: vec![Vec2::ZERO, Vec2::new(1.5, -2.0)] }),
+        };
+        high_scores.save(&path).unwrap();
+        assert_eq!(HighScores::load_or_default(&path), high_scores);
+
+        fs::write(&path, "not ron").unwrap();
+        assert_eq!(HighScores::load_or_default(&path), HighScores::default());
+        fs::remove_dir_all(folder).unwrap();
     }
 }
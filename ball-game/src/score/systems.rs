@@ -1,25 +1,79 @@
 use bevy::prelude::*;
+use std::path::Path;
 
 use super::resources::*;
 use crate::events::GameOver;
+use crate::ghost::resources::Trajectory;
+use crate::mode::GameMode;
+
+pub fn load_high_scores(mut commands: Commands, file: Res<HighScoresFile>) {
+    if let Some(path) = file.0 {
+        commands.insert_resource(HighScores::load_or_default(Path::new(path)));
+    }
+}
+
+// every round starts from zero, high scores are kept
+pub fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
 
 pub fn update_score(score: Res<Score>) {
     if score.is_changed() {
-        info!("Score: {}", score.value);
+        info!("Score: {} {:?}", score.value, score.players);
     }
 }
 
 pub fn update_high_scores(
     mut game_over_event_reader: EventReader<GameOver>,
     mut high_scores: ResMut<HighScores>,
+    trajectory: Res<Trajectory>,
+    mode: Res<GameMode>,
 ) {
     for event in game_over_event_reader.read() {
-        high_scores.scores.push(("Player".to_string(), event.score));
+        let name = match (*mode, event.winner) {
+            (GameMode::Solo, _) => "Player".to_string(),
+            (GameMode::Coop, _) => "Team".to_string(),
+            (GameMode::Versus, Some(winner)) => format!("Player {}", winner.number()),
+            // a draw has no winner to put on the board
+            (GameMode::Versus, None) => continue,
+        };
+        high_scores.scores.push((name, event.score));
+
+        // the ghost only races a single player
+        if *mode != GameMode::Solo {
+            continue;
+        }
+
+        // a tie keeps the earlier run
+        let is_best = high_scores
+            .best_run
+            .as_ref()
+            .is_none_or(|best| event.score > best.score);
+        if is_best {
+            high_scores.best_run = Some(BestRun {
+                score: event.score,
+                trajectory: trajectory.positions.clone(),
+            });
+        }
     }
 }
 
 pub fn high_scores_updated(high_scores: Res<HighScores>) {
     if high_scores.is_changed() {
-        println!("High Scores: {:?}", high_scores);
+        // the trajectory of the best run is too long to print
+        info!("High Scores: {:?}", high_scores.scores);
+    }
+}
+
+pub fn save_high_scores(high_scores: Res<HighScores>, file: Res<HighScoresFile>) {
+    let Some(path) = file.0 else {
+        return;
+    };
+    // the loaded scores are already saved
+    if !high_scores.is_changed() || high_scores.is_added() {
+        return;
+    }
+    if let Err(error) = high_scores.save(Path::new(path)) {
+        warn!("Couldn't save the high scores to {path}: {error}");
     }
 }
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Star {}
 
 
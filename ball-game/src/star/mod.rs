@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 
+use crate::{BallGameSet, STATE};
+
 pub mod components;
-mod resources;
+pub mod resources;
 mod systems;
 
+use components::Star;
 use resources::*;
 use systems::*;
 
@@ -17,8 +20,12 @@ pub struct StarPlugin;
 impl Plugin for StarPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<StarSpawnTimer>()
-            .add_systems(Startup, spawn_stars)
-            .add_systems(Update, tick_star_spawn_timer)
-            .add_systems(Update, spawn_stars_over_time);
+            .register_type::<Star>()
+            .register_type::<StarSpawnTimer>()
+            .add_systems(OnEnter(STATE), (reset_star_spawn_timer, spawn_stars).in_set(BallGameSet::Star))
+            .add_systems(FixedUpdate, (
+                tick_star_spawn_timer,
+                spawn_stars_over_time,
+            ).chain().in_set(BallGameSet::Star));
     }
 }
@@ -3,7 +3,8 @@ use bevy::prelude::*;
 pub const STAR_SPAWN_TIME: f32 = 1.0;
 
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
 pub struct StarSpawnTimer {
     pub timer: Timer,
 }
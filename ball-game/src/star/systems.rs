@@ -1,12 +1,15 @@
 use bevy::prelude::*;
 use bevy::window::{ Window,  PrimaryWindow};
-use rand::random;
+use rand::Rng;
 
 use crate::enemy::components::Enemy;
 use crate::enemy::{SAFE_DISTANCE};
 
 use super::components::Star;
 use super::resources::*;
+use crate::STATE;
+use crate::assets::BallGameAssets;
+use crate::rng::GameRng;
 use crate::star::{NUMBER_OF_STARS, MAX_ATTEMPTS};
 
 
@@ -14,7 +17,8 @@ pub fn spawn_stars(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
     enemy_query: Query<&Transform, With<Enemy>>,
-    asset_server: Res<AssetServer>,
+    assets: Res<BallGameAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     let window = window_query.single().unwrap();
     // Collect enemy positions (2D)
@@ -27,8 +31,8 @@ pub fn spawn_stars(
         // Retry loop: pick until far enough or give up
         loop {
             pos2d = Vec2::new(
-                random::<f32>() * window.width(),
-                random::<f32>() * window.height(),
+                rng.random::<f32>() * window.width(),
+                rng.random::<f32>() * window.height(),
             );
             // Check distance to every enemy
             if enemy_positions
@@ -42,13 +46,18 @@ pub fn spawn_stars(
         }
 
         commands.spawn((
-            Sprite::from_image(asset_server.load("sprites/star.png")),
+            Sprite::from_image(assets.star.clone()),
             Transform::from_xyz(pos2d.x, pos2d.y, 0.0),
             Star {},
+            StateScoped(STATE),
         ));
     }
 }
 
+pub fn reset_star_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>) {
+    *star_spawn_timer = StarSpawnTimer::default();
+}
+
 pub fn tick_star_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>, time: Res<Time>) {
     star_spawn_timer.timer.tick(time.delta());
 }
@@ -56,20 +65,22 @@ pub fn tick_star_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>, time:
 pub fn spawn_stars_over_time(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    assets: Res<BallGameAssets>,
+    mut rng: ResMut<GameRng>,
     star_spawn_timer: Res<StarSpawnTimer>,
 ) {
     if star_spawn_timer.timer.finished() {
         let window = window_query.single().unwrap();
         let pos2d = Vec2::new(
-            random::<f32>() * window.width(),
-            random::<f32>() * window.height(),
+            rng.random::<f32>() * window.width(),
+            rng.random::<f32>() * window.height(),
         );
 
         commands.spawn((
-            Sprite::from_image(asset_server.load("sprites/star.png")),
+            Sprite::from_image(assets.star.clone()),
             Transform::from_xyz(pos2d.x, pos2d.y, 0.0),
             Star {},
+            StateScoped(STATE),
         ));
     }
 }
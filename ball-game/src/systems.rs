@@ -1,40 +1,36 @@
-use bevy::app::AppExit;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use bevy::input::ButtonInput;
 
-use crate::events::*;
+use action_map::ActionState;
 
-pub fn spawn_camera(
-    mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-) {
+use crate::LOADING;
+use crate::actions::GameAction;
+use crate::events::*;
+use crate::mode::GameMode;
 
-    let window = window_query.single().expect("Primary Window not found?");
-  
-    commands.spawn((
-        Camera2d,
-        Transform::from_xyz(
-            window.width()  / 2.0,
-            window.height() / 2.0,
-            0.0,
-        ),
-    ));
+pub fn handle_game_over(mut game_over_event_reader: EventReader<GameOver>, mode: Res<GameMode>) {
+    for event in game_over_event_reader.read() {
+        match (*mode, event.winner) {
+            (GameMode::Versus, Some(winner)) => info!("Player {} wins with {} stars!", winner.number(), event.score),
+            (GameMode::Versus, None) => info!("It's a draw at {} stars each!", event.score),
+            _ => info!("Your final score is: {}", event.score),
+        }
+    }
 }
 
-
-
-pub fn exit_game(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut app_exit_event_writer: EventWriter<AppExit>,
+// switch to the next mode and start a new round in it
+pub fn cycle_mode(
+    actions: Res<ActionState<GameAction>>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<common::AppState>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
-        app_exit_event_writer.write(AppExit::Success);
+    if actions.just_pressed(GameAction::CycleMode) {
+        *mode = mode.next();
+        info!("Starting a {} round", mode.name());
+        next_state.set(LOADING);
     }
 }
 
-pub fn handle_game_over(mut game_over_event_reader: EventReader<GameOver>) {
-    for event in game_over_event_reader.read() {
-        println!("Your final score is: {}", event.score.to_string());
-    }
+// the other games may have changed the background
+pub fn reset_clear_color(mut commands: Commands) {
+    commands.insert_resource(ClearColor::default());
 }
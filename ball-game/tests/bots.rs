@@ -0,0 +1,52 @@
+// the reference bots played headless by the episode runner
+
+use ball_game::bot::policies;
+use ball_game::bot::runner::{Distribution, RunnerConfig, play_episodes};
+use ball_game::mode::GameMode;
+
+// a minute of play at most
+const MAX_TICKS: u32 = 64 * 60;
+
+fn config(policy: &str, mode: GameMode) -> RunnerConfig {
+    RunnerConfig {
+        policy: policy.to_string(),
+        episodes: 2,
+        mode,
+        seed: 5,
+        max_ticks: MAX_TICKS,
+    }
+}
+
+#[test]
+fn the_reference_bots_collect_stars() {
+    for policy in policies::POLICY_NAMES {
+        let episodes = play_episodes(&config(policy, GameMode::Solo), || policies::from_name(policy).unwrap());
+        assert_eq!(episodes.len(), 2);
+        assert!(episodes.iter().any(|episode| episode.score > 0), "{policy} didn't collect a star");
+        for episode in &episodes {
+            assert!(episode.ticks <= MAX_TICKS);
+            assert_eq!(episode.timed_out, episode.ticks == MAX_TICKS);
+        }
+    }
+}
+
+#[test]
+fn an_episode_plays_out_the_same_from_its_seed() {
+    let config = config(policies::PotentialField::NAME, GameMode::Versus);
+    let play = || play_episodes(&config, || policies::from_name(policies::PotentialField::NAME).unwrap());
+    let (first, second) = (play(), play());
+    for (a, b) in first.iter().zip(&second) {
+        assert_eq!((a.seed, a.score, &a.players, a.ticks), (b.seed, b.score, &b.players, b.ticks));
+        assert_eq!(a.players.len(), 2);
+    }
+}
+
+#[test]
+fn distributions_spread_the_values() {
+    let distribution = Distribution::of([4.0, 1.0, 3.0, 2.0, 5.0]);
+    assert_eq!((distribution.min, distribution.max, distribution.mean), (1.0, 5.0, 3.0));
+    assert_eq!((distribution.p10, distribution.median, distribution.p90), (1.0, 3.0, 5.0));
+    assert!((distribution.std_dev - 2.0f64.sqrt()).abs() < 1e-9);
+    assert_eq!(distribution.histogram.values().sum::<usize>(), 5);
+    assert_eq!(Distribution::of(Vec::new()), Distribution::default());
+}
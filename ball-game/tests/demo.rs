@@ -0,0 +1,39 @@
+// the demo playing the whole game headless, as a smoke test
+
+use std::time::Duration;
+
+use ball_game::{LOADING, NAME, STATE};
+use ball_game::headless::{enter_game, headless_app};
+use ball_game::rng::GameRng;
+use ball_game::score::resources::{HighScores, Score};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{AppState, Attract};
+
+const TICK: Duration = Duration::from_nanos(1_000_000_000 / 64);
+// a minute of play
+const TICKS: usize = 64 * 60;
+
+#[test]
+fn the_demo_collects_stars_and_keeps_going() {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(GameRng::seeded(3))
+        .insert_resource(Attract(NAME));
+    app.finish();
+    app.cleanup();
+    enter_game(&mut app);
+
+    let mut best_score = 0;
+    for _ in 0..TICKS {
+        app.update();
+        best_score = best_score.max(app.world().resource::<Score>().value);
+    }
+
+    assert!(best_score > 0, "the demo didn't collect a star");
+    // a round which ended starts over
+    let state = app.world().resource::<State<AppState>>().get().clone();
+    assert!(state == STATE || state == LOADING, "the demo stopped in {state:?}");
+    assert!(app.world().resource::<HighScores>().scores.is_empty(), "the demo went on the high scores");
+}
@@ -0,0 +1,144 @@
+// a server and its clients in one process, talking over loopback
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use ball_game::enemy::components::Enemy;
+use ball_game::headless::{enter_game, headless_app};
+use ball_game::mode::GameMode;
+use ball_game::net::client::{NetClient, Remote};
+use ball_game::net::server::NetServer;
+use ball_game::player::components::{Player, PlayerId};
+use ball_game::replay::resources::ReplayMode;
+use ball_game::rng::GameRng;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+// one fixed tick per update, on the server and the clients alike
+const TICK: Duration = Duration::from_nanos(1_000_000_000 / 64);
+
+fn app_with(configure: impl FnOnce(&mut App)) -> App {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(ReplayMode::Off);
+    configure(&mut app);
+    app.finish();
+    app.cleanup();
+    enter_game(&mut app);
+    app
+}
+
+fn server() -> (App, SocketAddr) {
+    let server = NetServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let app = app_with(|app| {
+        app.insert_resource(server)
+            .insert_resource(GameMode::Coop)
+            .insert_resource(GameRng::seeded(7));
+    });
+    (app, addr)
+}
+
+fn client(server: SocketAddr) -> App {
+    app_with(|app| {
+        app.insert_resource(NetClient::connect(server).unwrap());
+    })
+}
+
+// update every app `ticks` times, giving the datagrams time to arrive in between
+fn run(apps: &mut [&mut App], ticks: usize) {
+    for _ in 0..ticks {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn local_player(client: &mut App) -> PlayerId {
+    client.world().resource::<NetClient>().player().expect("the client didn't join")
+}
+
+fn player_position(app: &mut App, id: PlayerId) -> Option<Vec2> {
+    app.world_mut()
+        .query::<(&Transform, &Player)>()
+        .iter(app.world())
+        .find(|(_, player)| player.id == id)
+        .map(|(transform, _)| transform.translation.truncate())
+}
+
+#[test]
+fn clients_join_and_follow_the_server() {
+    let (mut server, addr) = server();
+    let mut one = client(addr);
+    let mut two = client(addr);
+    run(&mut [&mut server, &mut one, &mut two], 60);
+
+    let players = [local_player(&mut one), local_player(&mut two)];
+    assert_ne!(players[0], players[1], "both clients got the same player");
+    assert_eq!(one.world().resource::<GameMode>(), &GameMode::Coop);
+
+    let server_enemies: HashSet<u64> = server
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .iter(server.world())
+        .map(Entity::to_bits)
+        .collect();
+    for client in [&mut one, &mut two] {
+        for id in PlayerId::ALL {
+            assert!(player_position(client, id).is_some(), "player {} is missing", id.number());
+        }
+        let enemies: Vec<u64> = client
+            .world_mut()
+            .query::<&Remote>()
+            .iter(client.world())
+            .filter_map(|remote| match remote {
+                Remote::Enemy(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        assert!(!enemies.is_empty(), "the enemies weren't sent");
+        assert!(enemies.iter().all(|id| server_enemies.contains(id)), "an enemy isn't on the server");
+    }
+}
+
+#[test]
+fn the_local_player_is_predicted_and_reconciled() {
+    let (mut server, addr) = server();
+    let mut client = client(addr);
+    run(&mut [&mut server, &mut client], 30);
+    let id = local_player(&mut client);
+    let start = player_position(&mut client, id).unwrap();
+
+    // the client moves before the server hears of it
+    client.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyD);
+    client.update();
+    let predicted = player_position(&mut client, id).unwrap();
+    assert!(predicted.x > start.x, "the local player didn't move right away");
+    assert_eq!(player_position(&mut server, id), Some(start));
+
+    run(&mut [&mut server, &mut client], 20);
+    client.world_mut().resource_mut::<ButtonInput<KeyCode>>().release(KeyCode::KeyD);
+    run(&mut [&mut server, &mut client], 20);
+
+    // once every input is applied, the client agrees with the server
+    let on_server = player_position(&mut server, id).unwrap();
+    assert!(on_server.x > start.x, "the server didn't apply the inputs");
+    assert_eq!(player_position(&mut client, id), Some(on_server));
+}
+
+#[test]
+fn a_full_server_refuses_more_clients() {
+    let (mut server, addr) = server();
+    let mut one = client(addr);
+    let mut two = client(addr);
+    run(&mut [&mut server, &mut one, &mut two], 10);
+    let mut three = client(addr);
+    run(&mut [&mut server, &mut one, &mut two, &mut three], 10);
+
+    assert!(three.world().resource::<NetClient>().refused());
+    assert_eq!(three.world().resource::<NetClient>().player(), None);
+    assert_eq!(server.world().resource::<NetServer>().players().len(), 2);
+}
@@ -2,7 +2,7 @@
 
 #better for production, smaller binary
 cargo xwin build --release --target x86_64-pc-windows-msvc &&
-cp -f target/x86_64-pc-windows-msvc/release/ball-game.exe .
+cp -f ../target/x86_64-pc-windows-msvc/release/ball-game.exe .
 exec ./ball-game.exe "$@"
 #--release this is for final production version
 #cargo build --release --target x86_64-pc-windows-gnu &&
@@ -0,0 +1,87 @@
+//! The input actions of the game, and the keys and gamepad inputs they are bound to by default.
+
+use action_map::{Action, AxisDirection, Binding};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where rebound controls are saved, apart from the other games run by the launcher
+pub const CONTROLS_PATH: &str = "config/breakout-controls.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BreakoutAction {
+    PaddleLeft,
+    PaddleRight,
+    /// Move the second player's paddle, in versus
+    TopPaddleLeft,
+    TopPaddleRight,
+    /// Launch balls stuck to a sticky paddle
+    Launch,
+    SpawnDebugBall,
+    /// Export the paddle, balls and bricks to a scene file, and import them back
+    ExportScene,
+    ImportScene,
+    /// Switch between the classic and versus games
+    ToggleVersus,
+    /// Hand the top paddle to the AI, or back to the second player
+    ToggleTopAi,
+    /// Hand the bottom paddle to the autopilot, or back
+    ToggleAutopilot,
+    Exit,
+}
+
+impl Action for BreakoutAction {
+    const ALL: &'static [BreakoutAction] = &[
+        BreakoutAction::PaddleLeft,
+        BreakoutAction::PaddleRight,
+        BreakoutAction::TopPaddleLeft,
+        BreakoutAction::TopPaddleRight,
+        BreakoutAction::Launch,
+        BreakoutAction::SpawnDebugBall,
+        BreakoutAction::ExportScene,
+        BreakoutAction::ImportScene,
+        BreakoutAction::ToggleVersus,
+        BreakoutAction::ToggleTopAi,
+        BreakoutAction::ToggleAutopilot,
+        BreakoutAction::Exit,
+    ];
+
+    fn default_bindings(self) -> Vec<Binding> {
+        match self {
+            BreakoutAction::PaddleLeft => vec![
+                Binding::Key(KeyCode::ArrowLeft),
+                Binding::PadButton(0, GamepadButton::DPadLeft),
+                Binding::PadAxis(0, GamepadAxis::LeftStickX, AxisDirection::Negative),
+            ],
+            BreakoutAction::PaddleRight => vec![
+                Binding::Key(KeyCode::ArrowRight),
+                Binding::PadButton(0, GamepadButton::DPadRight),
+                Binding::PadAxis(0, GamepadAxis::LeftStickX, AxisDirection::Positive),
+            ],
+            BreakoutAction::TopPaddleLeft => vec![
+                Binding::Key(KeyCode::KeyA),
+                Binding::PadButton(1, GamepadButton::DPadLeft),
+                Binding::PadAxis(1, GamepadAxis::LeftStickX, AxisDirection::Negative),
+            ],
+            BreakoutAction::TopPaddleRight => vec![
+                Binding::Key(KeyCode::KeyD),
+                Binding::PadButton(1, GamepadButton::DPadRight),
+                Binding::PadAxis(1, GamepadAxis::LeftStickX, AxisDirection::Positive),
+            ],
+            BreakoutAction::Launch => vec![
+                Binding::Key(KeyCode::ArrowUp),
+                Binding::Mouse(MouseButton::Left),
+                Binding::GamepadButton(GamepadButton::South),
+            ],
+            BreakoutAction::SpawnDebugBall => vec![Binding::Key(KeyCode::KeyB)],
+            BreakoutAction::ExportScene => vec![Binding::Key(KeyCode::F6)],
+            BreakoutAction::ImportScene => vec![Binding::Key(KeyCode::F7)],
+            BreakoutAction::ToggleVersus => vec![Binding::Key(KeyCode::F8)],
+            BreakoutAction::ToggleTopAi => vec![Binding::Key(KeyCode::F9)],
+            BreakoutAction::ToggleAutopilot => vec![Binding::Key(KeyCode::F10)],
+            BreakoutAction::Exit => vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::GamepadButton(GamepadButton::Select),
+            ],
+        }
+    }
+}
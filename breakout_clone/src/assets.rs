@@ -0,0 +1,23 @@
+//! The files breakout loads before playing.
+
+use bevy::prelude::*;
+use common::AssetCollection;
+
+/// Every asset of the game, loaded while in [`crate::LOADING`]
+#[derive(Resource)]
+pub struct BreakoutAssets {
+    /// Font of the scoreboard
+    pub font: Handle<Font>,
+}
+
+impl AssetCollection for BreakoutAssets {
+    fn load(asset_server: &AssetServer) -> Self {
+        Self {
+            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        }
+    }
+
+    fn handles(&self) -> Vec<UntypedHandle> {
+        vec![self.font.clone().untyped()]
+    }
+}
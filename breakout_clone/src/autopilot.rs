@@ -0,0 +1,413 @@
+//! A computer player for the bottom paddle.
+//!
+//! While the [`Autopilot`] resource exists, it predicts where each ball will cross the paddle by
+//! following its bounces off the [`Wall`]s, and steers the [`PaddleIntent`] towards the first one
+//! to arrive, like a player would. Balls caught by a sticky paddle are held for a moment and
+//! launched from where it aims. Its reaction delay and aiming error make it beatable. It plays
+//! the launcher's demo when nobody is at the menu, and drives the headless soak runs.
+
+use action_map::ActionState;
+use bevy::{math::bounding::Aabb2d, prelude::*};
+use common::Attract;
+use rand::Rng;
+
+use crate::{
+    BALL_DIAMETER, Ball, NAME, Paddle, STATE, Velocity, Wall, actions::BreakoutAction, move_paddle,
+    paddle_input::PaddleIntent, rng::GameRng,
+};
+
+// The paddle slows down when its aim is closer than this to its center, instead of jittering
+const SLOWDOWN_DISTANCE: f32 = 20.0;
+// A prediction moving further than this from the planned one means the ball changed course
+const REPLAN_DISTANCE: f32 = 10.0;
+// Bounces followed before giving up on a prediction, e.g. for a ball sliding along a wall
+const MAX_PREDICTED_BOUNCES: usize = 16;
+// Collisions closer than this in time are the bounce just followed
+const MIN_HIT_TIME: f32 = 1e-4;
+// Balls stuck to a sticky paddle are held at least this many seconds, then launched once the
+// paddle is this close to its aim
+const LAUNCH_HOLD: f32 = 0.5;
+const LAUNCH_DISTANCE: f32 = 5.0;
+
+/// Plugin letting the [`Autopilot`] play, and toggling it
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutopilotPlan>()
+            .add_systems(OnEnter(STATE), start_demo)
+            .add_systems(OnExit(STATE), stop_demo)
+            .add_systems(
+                FixedUpdate,
+                steer_paddle
+                    .before(move_paddle)
+                    .run_if(in_state(STATE).and(resource_exists::<Autopilot>)),
+            )
+            .add_systems(Update, toggle_autopilot.run_if(in_state(STATE)));
+    }
+}
+
+/// The bottom paddle is played by the computer while this exists
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Autopilot {
+    /// Seconds before the paddle reacts to a ball changing course
+    pub reaction_delay: f32,
+    /// How far from the predicted landing point the paddle may aim, at most, in world units
+    pub error: f32,
+}
+
+impl Autopilot {
+    /// Slow and sloppy enough to miss now and then
+    pub const EASY: Autopilot = Autopilot {
+        reaction_delay: 0.4,
+        error: 70.0,
+    };
+    pub const NORMAL: Autopilot = Autopilot {
+        reaction_delay: 0.2,
+        error: 35.0,
+    };
+    /// Reacts at once and never misses its aim
+    pub const HARD: Autopilot = Autopilot {
+        reaction_delay: 0.0,
+        error: 0.0,
+    };
+
+    /// The difficulty called `easy`, `normal` or `hard`
+    pub fn from_name(name: &str) -> Option<Autopilot> {
+        match name {
+            "easy" => Some(Autopilot::EASY),
+            "normal" => Some(Autopilot::NORMAL),
+            "hard" => Some(Autopilot::HARD),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Autopilot::NORMAL
+    }
+}
+
+/// Where the autopilot is taking the paddle
+#[derive(Resource, Debug, Default)]
+struct AutopilotPlan {
+    /// The predicted landing point the paddle is heading for
+    planned: Option<f32>,
+    /// Where the paddle is heading, the planned point with the aiming error added
+    aim: f32,
+    /// Counts the reaction delay down, once the prediction moved away from the planned point
+    reaction: Option<Timer>,
+    /// Counts down the hold of the balls stuck to the paddle, while there are any
+    hold: Option<Timer>,
+}
+
+/// Play the launcher's demo, see [`Attract`]
+fn start_demo(mut commands: Commands, attract: Option<Res<Attract>>) {
+    if attract.is_some_and(|attract| attract.0 == NAME) {
+        commands.insert_resource(Autopilot::default());
+    }
+}
+
+fn stop_demo(mut commands: Commands, attract: Option<Res<Attract>>) {
+    if attract.is_some_and(|attract| attract.0 == NAME) {
+        commands.remove_resource::<Autopilot>();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn steer_paddle(
+    autopilot: Res<Autopilot>,
+    mut plan: ResMut<AutopilotPlan>,
+    mut intent: ResMut<PaddleIntent>,
+    paddle_transform: Single<&Transform, With<Paddle>>,
+    ball_query: Query<(&Transform, Option<&Velocity>), With<Ball>>,
+    wall_query: Query<&Transform, With<Wall>>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+) {
+    // The ball's center bounces off the walls grown by its radius
+    let walls: Vec<Aabb2d> = wall_query
+        .iter()
+        .map(|transform| {
+            Aabb2d::new(
+                transform.translation.truncate(),
+                (transform.scale.truncate() + BALL_DIAMETER) / 2.0,
+            )
+        })
+        .collect();
+    let paddle_top =
+        paddle_transform.translation.y + paddle_transform.scale.y / 2.0 + BALL_DIAMETER / 2.0;
+
+    // Head for the ball arriving first, balls stuck to a sticky paddle have no velocity
+    let landing = ball_query
+        .iter()
+        .filter_map(|(transform, velocity)| {
+            predict_crossing(
+                transform.translation.truncate(),
+                **velocity?,
+                &walls,
+                paddle_top,
+            )
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(x, _)| x);
+
+    let changed_course = match (plan.planned, landing) {
+        (Some(planned), Some(landing)) => (landing - planned).abs() > REPLAN_DISTANCE,
+        (planned, landing) => planned.is_some() != landing.is_some(),
+    };
+    if !changed_course {
+        plan.reaction = None;
+    } else if plan
+        .reaction
+        .get_or_insert_with(|| Timer::from_seconds(autopilot.reaction_delay, TimerMode::Once))
+        .tick(time.delta())
+        .finished()
+    {
+        // Wait in the middle when there is nothing to catch
+        plan.aim = landing.map_or(0.0, |landing| {
+            landing + rng.random_range(-autopilot.error..=autopilot.error)
+        });
+        plan.planned = landing;
+        plan.reaction = None;
+    }
+
+    // Carry a caught ball to where the paddle is heading before launching it
+    let stuck = ball_query.iter().any(|(_, velocity)| velocity.is_none());
+    let held = if stuck {
+        plan.hold
+            .get_or_insert_with(|| Timer::from_seconds(LAUNCH_HOLD, TimerMode::Once))
+            .tick(time.delta())
+            .finished()
+    } else {
+        plan.hold = None;
+        false
+    };
+
+    let to_aim = plan.aim - paddle_transform.translation.x;
+    intent.axis = (to_aim / SLOWDOWN_DISTANCE).clamp(-1.0, 1.0);
+    intent.target_x = None;
+    intent.launch = held && to_aim.abs() <= LAUNCH_DISTANCE;
+}
+
+/// Where a ball at `position` moving with `velocity` crosses `target_y` on its way down, and in
+/// how many seconds, following its bounces off `walls`.
+///
+/// The bricks and the other balls are left out, the ball changing course when it hits them.
+fn predict_crossing(
+    mut position: Vec2,
+    mut velocity: Vec2,
+    walls: &[Aabb2d],
+    target_y: f32,
+) -> Option<(f32, f32)> {
+    let mut elapsed = 0.0;
+    for _ in 0..MAX_PREDICTED_BOUNCES {
+        let crossing = (velocity.y < 0.0 && position.y >= target_y)
+            .then(|| (target_y - position.y) / velocity.y);
+        let hits: Vec<(f32, usize)> = walls
+            .iter()
+            .filter_map(|wall| time_to_hit(position, velocity, wall))
+            .collect();
+        let hit = hits.iter().map(|(time, _)| *time).min_by(f32::total_cmp);
+
+        match (crossing, hit) {
+            (Some(crossing), hit) if hit.is_none_or(|time| crossing <= time) => {
+                return Some((position.x + velocity.x * crossing, elapsed + crossing));
+            }
+            (_, Some(time)) => {
+                position += velocity * time;
+                elapsed += time;
+                // Into a corner, both walls turn the ball around
+                let mut reflected = BVec2::FALSE;
+                for &(_, axis) in hits
+                    .iter()
+                    .filter(|(other, _)| *other - time < MIN_HIT_TIME)
+                {
+                    reflected.set(axis, true);
+                }
+                velocity = Vec2::select(reflected, -velocity, velocity);
+            }
+            (_, None) => return None,
+        }
+    }
+    None
+}
+
+/// When a point at `position` moving with `velocity` enters `wall`, and the axis of the side it
+/// enters through, 0 for x and 1 for y
+fn time_to_hit(position: Vec2, velocity: Vec2, wall: &Aabb2d) -> Option<(f32, usize)> {
+    let mut enter = [f32::NEG_INFINITY; 2];
+    let mut exit = f32::INFINITY;
+    for axis in 0..2 {
+        let (min, max) = (wall.min[axis], wall.max[axis]);
+        if velocity[axis] == 0.0 {
+            if position[axis] < min || position[axis] > max {
+                return None;
+            }
+            continue;
+        }
+        let to_min = (min - position[axis]) / velocity[axis];
+        let to_max = (max - position[axis]) / velocity[axis];
+        enter[axis] = to_min.min(to_max);
+        exit = exit.min(to_min.max(to_max));
+    }
+
+    let (time, axis) = if enter[0] > enter[1] {
+        (enter[0], 0)
+    } else {
+        (enter[1], 1)
+    };
+    (time > MIN_HIT_TIME && time <= exit).then_some((time, axis))
+}
+
+/// Hand the bottom paddle to the computer or back with `ToggleAutopilot` (F10 by default)
+fn toggle_autopilot(
+    mut commands: Commands,
+    actions: Res<ActionState<BreakoutAction>>,
+    autopilot: Option<Res<Autopilot>>,
+) {
+    if actions.just_pressed(BreakoutAction::ToggleAutopilot) {
+        if autopilot.is_some() {
+            commands.remove_resource::<Autopilot>();
+            info!("The autopilot is off");
+        } else {
+            commands.insert_resource(Autopilot::default());
+            info!("The autopilot is on");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A box 200 wide, open at the bottom, already grown by the ball radius
+    fn walls() -> [Aabb2d; 3] {
+        [
+            Aabb2d {
+                min: Vec2::new(-110.0, -400.0),
+                max: Vec2::new(-100.0, 110.0),
+            },
+            Aabb2d {
+                min: Vec2::new(100.0, -400.0),
+                max: Vec2::new(110.0, 110.0),
+            },
+            Aabb2d {
+                min: Vec2::new(-110.0, 100.0),
+                max: Vec2::new(110.0, 110.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn predicts_where_balls_cross_the_paddle() {
+        let target_y = -150.0;
+        // (case, position, velocity, crossing x and seconds until then)
+        let cases = [
+            (
+                "direct drop",
+                Vec2::new(20.0, 50.0),
+                Vec2::new(0.0, -100.0),
+                Some((20.0, 2.0)),
+            ),
+            (
+                "straight down the middle at an angle",
+                Vec2::new(-50.0, 50.0),
+                Vec2::new(50.0, -100.0),
+                Some((50.0, 2.0)),
+            ),
+            (
+                "single side wall bounce",
+                Vec2::new(0.0, 50.0),
+                Vec2::new(100.0, -100.0),
+                Some((0.0, 2.0)),
+            ),
+            (
+                "double side wall bounce",
+                Vec2::new(0.0, 50.0),
+                Vec2::new(200.0, -100.0),
+                Some((0.0, 2.0)),
+            ),
+            (
+                "moving upward, off the ceiling",
+                Vec2::new(30.0, 50.0),
+                Vec2::new(0.0, 100.0),
+                Some((30.0, 3.0)),
+            ),
+            (
+                "moving upward into a corner, then off the other side",
+                Vec2::new(0.0, 0.0),
+                Vec2::new(100.0, 100.0),
+                Some((-50.0, 3.5)),
+            ),
+            (
+                "already past the paddle",
+                Vec2::new(0.0, -200.0),
+                Vec2::new(0.0, -100.0),
+                None,
+            ),
+        ];
+
+        for (case, position, velocity, expected) in cases {
+            let crossing = predict_crossing(position, velocity, &walls(), target_y);
+            match (crossing, expected) {
+                (Some((x, time)), Some((expected_x, expected_time))) => {
+                    assert!(
+                        (x - expected_x).abs() < 1e-3 && (time - expected_time).abs() < 1e-3,
+                        "{case}: crossed at {x} after {time}s, expected {expected_x} after {expected_time}s"
+                    );
+                }
+                (crossing, expected) => assert_eq!(crossing, expected, "{case}"),
+            }
+        }
+    }
+
+    #[test]
+    fn a_ball_sliding_along_a_wall_is_never_predicted() {
+        // Bouncing back and forth between the side walls without ever coming down
+        let crossing = predict_crossing(Vec2::ZERO, Vec2::new(300.0, 0.0), &walls(), -150.0);
+        assert_eq!(crossing, None);
+    }
+
+    #[test]
+    fn finds_the_side_a_wall_is_entered_through() {
+        let [left, right, top] = walls();
+        // (case, position, velocity, wall, time and axis of the hit)
+        let cases = [
+            (
+                "head on",
+                Vec2::ZERO,
+                Vec2::new(50.0, 0.0),
+                right,
+                Some((2.0, 0)),
+            ),
+            (
+                "from below",
+                Vec2::ZERO,
+                Vec2::new(0.0, 25.0),
+                top,
+                Some((4.0, 1)),
+            ),
+            (
+                "diagonally",
+                Vec2::new(-50.0, 0.0),
+                Vec2::new(-100.0, 100.0),
+                left,
+                Some((0.5, 0)),
+            ),
+            ("moving away", Vec2::ZERO, Vec2::new(50.0, 0.0), left, None),
+            (
+                "passing alongside",
+                Vec2::new(0.0, -500.0),
+                Vec2::new(50.0, 0.0),
+                right,
+                None,
+            ),
+        ];
+
+        for (case, position, velocity, wall, expected) in cases {
+            assert_eq!(time_to_hit(position, velocity, &wall), expected, "{case}");
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Support for any number of balls in play at once.
+//!
+//! Balls are spawned through [`SpawnBallExt::spawn_ball`], bounce off each other,
+//! and a life is only lost once the last ball in play falls out of the bottom of the arena.
+
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+
+use action_map::ActionState;
+
+use crate::{
+    BALL_DIAMETER, BALL_SPEED, BALL_STARTING_POSITION, BOTTOM_WALL, Ball, CollisionEvent,
+    INITIAL_BALL_DIRECTION, LEFT_WALL, Lives, Paddle, RIGHT_WALL, STATE, Velocity, WALL_THICKNESS,
+    actions::BreakoutAction,
+};
+
+/// The mesh and material shared by every ball, so balls can be spawned after `setup`
+#[derive(Resource)]
+pub struct BallAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<ColorMaterial>,
+}
+
+/// Spawning balls from any system with access to [`Commands`]
+pub trait SpawnBallExt {
+    /// Spawn a ball at `translation` moving with `velocity`.
+    ///
+    /// The returned [`EntityCommands`] can be used to add extra components to the new ball.
+    fn spawn_ball(&mut self, translation: Vec3, velocity: Vec2) -> EntityCommands<'_>;
+}
+
+impl SpawnBallExt for Commands<'_, '_> {
+    fn spawn_ball(&mut self, translation: Vec3, velocity: Vec2) -> EntityCommands<'_> {
+        let ball = self
+            .spawn((
+                Transform::from_translation(translation)
+                    .with_scale(Vec2::splat(BALL_DIAMETER).extend(1.)),
+                Ball,
+                Velocity(velocity),
+                StateScoped(STATE),
+            ))
+            .id();
+
+        // The mesh and material are looked up once the command is applied,
+        // so callers don't need to fetch `BallAssets` themselves
+        self.queue(move |world: &mut World| {
+            let assets = world.resource::<BallAssets>();
+            let visuals = (
+                Mesh2d(assets.mesh.clone()),
+                MeshMaterial2d(assets.material.clone()),
+            );
+            if let Ok(mut ball) = world.get_entity_mut(ball) {
+                ball.insert(visuals);
+            }
+        });
+
+        self.entity(ball)
+    }
+}
+
+/// Remove balls which fell out of the bottom of the arena.
+///
+/// This runs before `check_for_collisions`, so balls are never reflected off the bottom wall.
+/// When the last ball in play is lost, a life is lost and a new ball is served.
+pub fn check_for_lost_balls(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    ball_query: Query<(Entity, &Transform), With<Ball>>,
+) {
+    let floor = Aabb2d::new(
+        Vec2::new(0., BOTTOM_WALL),
+        Vec2::new((RIGHT_WALL - LEFT_WALL) / 2., WALL_THICKNESS / 2.),
+    );
+
+    let mut balls_in_play = 0;
+    let mut balls_lost = 0;
+    for (ball, ball_transform) in &ball_query {
+        balls_in_play += 1;
+        let ball_circle =
+            BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.);
+        if ball_circle.intersects(&floor) {
+            commands.entity(ball).despawn();
+            balls_lost += 1;
+        }
+    }
+
+    if balls_lost > 0 && balls_lost == balls_in_play {
+        **lives = lives.saturating_sub(1);
+        commands.spawn_ball(
+            BALL_STARTING_POSITION,
+            INITIAL_BALL_DIRECTION.normalize() * BALL_SPEED,
+        );
+    }
+}
+
+/// Bounce balls off each other, treating them as perfectly elastic circles of equal mass.
+pub fn check_for_ball_collisions(
+    mut ball_query: Query<(&mut Velocity, &Transform), With<Ball>>,
+    mut collision_events: EventWriter<CollisionEvent>,
+) {
+    let mut combinations = ball_query.iter_combinations_mut();
+    while let Some([(mut velocity_a, transform_a), (mut velocity_b, transform_b)]) =
+        combinations.fetch_next()
+    {
+        let offset = transform_b.translation.truncate() - transform_a.translation.truncate();
+        if offset.length() > BALL_DIAMETER {
+            continue;
+        }
+        let Some(normal) = offset.try_normalize() else {
+            continue;
+        };
+
+        // Only bounce balls which are moving towards each other,
+        // otherwise overlapping balls would stay stuck together
+        let approach_speed = (**velocity_a - **velocity_b).dot(normal);
+        if approach_speed <= 0.0 {
+            continue;
+        }
+
+        // Equal masses exchange the part of their velocity along the collision normal
+        **velocity_a -= approach_speed * normal;
+        **velocity_b += approach_speed * normal;
+        collision_events.write_default();
+    }
+}
+
+/// Serve an extra ball from the paddle on `SpawnDebugBall` (B by default), to test multi-ball without power-ups
+pub fn spawn_debug_ball(
+    mut commands: Commands,
+    actions: Res<ActionState<BreakoutAction>>,
+    paddle_transform: Single<&Transform, With<Paddle>>,
+) {
+    if actions.just_pressed(BreakoutAction::SpawnDebugBall) {
+        let translation = paddle_transform.translation
+            + Vec3::new(0.0, paddle_transform.scale.y / 2.0 + BALL_DIAMETER, 1.0);
+        let direction = Vec2::new(-INITIAL_BALL_DIRECTION.x, -INITIAL_BALL_DIRECTION.y);
+        commands.spawn_ball(translation, direction.normalize() * BALL_SPEED);
+    }
+}
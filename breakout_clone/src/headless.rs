@@ -0,0 +1,68 @@
+//! Breakout without a window, rendering or audio.
+//!
+//! The asset loaders are missing as well, so the scoreboard font and the sounds are left out the
+//! way missing files are. Used by the [`crate::soak`] runs and the tests.
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    app::ScheduleRunnerPlugin, asset::AssetPlugin, audio::AudioSource, ecs::schedule::ExecutorKind,
+    input::InputPlugin, prelude::*, state::app::StatesPlugin, window::PrimaryWindow,
+};
+use common::{AddGameExt, AppState};
+
+use crate::{BreakoutPlugin, LOADING, STATE, paddle_input::PaddleInputSettings};
+
+/// How often [`App::run`] updates, once per default fixed tick
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / 64);
+// The missing assets fail fast, loading shouldn't take longer than this
+const LOADING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The game in its loading state.
+///
+/// It still has to be finished with [`App::finish`] and [`App::cleanup`], so more plugins and
+/// resources can be added first.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(TICK)),
+        StatesPlugin,
+        AssetPlugin::default(),
+        InputPlugin,
+    ))
+    .init_asset::<Image>()
+    .init_asset::<Font>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .init_asset::<AudioSource>()
+    // Read by the paddle controls, which never see the pointer move
+    .add_event::<CursorMoved>()
+    .insert_resource(PaddleInputSettings {
+        pointer_enabled: false,
+        ..default()
+    })
+    .insert_state(LOADING)
+    .add_game(BreakoutPlugin);
+
+    app.world_mut().spawn((Window::default(), PrimaryWindow));
+    // A single thread keeps the order of the random draws, so a seeded run plays out the same
+    app.edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    app.edit_schedule(Update, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+    app
+}
+
+/// Update the finished app until its assets are loaded and the game has started
+pub fn enter_game(app: &mut App) {
+    let loading_started = Instant::now();
+    while *app.world().resource::<State<AppState>>() != STATE {
+        if loading_started.elapsed() > LOADING_TIMEOUT {
+            panic!("breakout didn't finish loading within {LOADING_TIMEOUT:?}");
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,718 @@
+//! A simplified implementation of the classic game "Breakout".
+//!
+//! [`BreakoutPlugin`] can be played on its own or from the launcher. [`stepping_plugin`]
+//! demonstrates Bevy's stepping capabilities if compiled with the `bevy_debug_stepping` feature.
+//! [`headless_app`] runs the game without a window, for the [`soak`] runs of the [`Autopilot`].
+
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, BoundingVolume, IntersectsVolume},
+    prelude::*,
+};
+
+mod actions;
+mod assets;
+mod autopilot;
+mod balls;
+mod headless;
+mod paddle_input;
+mod powerups;
+mod rng;
+mod soak;
+mod versus;
+
+use action_map::ActionMapPlugin;
+use actions::{BreakoutAction, CONTROLS_PATH};
+use assets::BreakoutAssets;
+use balls::{BallAssets, SpawnBallExt};
+use common::{
+    AddAssetsExt, AddGameExt, AddSceneExt, AddSoundExt, AppState, GamePlugin, PlaySoundExt,
+    SceneImported, SceneTypes, Sound, exit_on,
+};
+use paddle_input::PaddleIntent;
+use serde::{Deserialize, Serialize};
+use stepping::SteppingPlugin;
+use versus::{LastTouch, Side, TopPaddle, VersusScore};
+
+pub use autopilot::Autopilot;
+pub use headless::{enter_game, headless_app};
+pub use rng::GameRng;
+pub use soak::{SoakConfig, SoakReport, soak};
+pub use versus::{BreakoutMode, TopPaddleControl};
+
+// These constants are defined in `Transform` units.
+// Using the default 2D camera they correspond 1:1 with screen pixels.
+const PADDLE_SIZE: Vec2 = Vec2::new(120.0, 20.0);
+const GAP_BETWEEN_PADDLE_AND_FLOOR: f32 = 60.0;
+const PADDLE_SPEED: f32 = 500.0;
+// How close can the paddle get to the wall
+const PADDLE_PADDING: f32 = 10.0;
+
+// We set the z-value of the ball to 1 so it renders on top in the case of overlapping sprites.
+const BALL_STARTING_POSITION: Vec3 = Vec3::new(0.0, -50.0, 1.0);
+const BALL_DIAMETER: f32 = 30.;
+const BALL_SPEED: f32 = 400.0;
+const INITIAL_BALL_DIRECTION: Vec2 = Vec2::new(0.5, -0.5);
+
+const STARTING_LIVES: usize = 3;
+
+const WALL_THICKNESS: f32 = 10.0;
+// x coordinates
+const LEFT_WALL: f32 = -450.;
+const RIGHT_WALL: f32 = 450.;
+// y coordinates
+const BOTTOM_WALL: f32 = -300.;
+const TOP_WALL: f32 = 300.;
+
+const BRICK_SIZE: Vec2 = Vec2::new(100., 30.);
+// These values are exact
+const GAP_BETWEEN_PADDLE_AND_BRICKS: f32 = 270.0;
+const GAP_BETWEEN_BRICKS: f32 = 5.0;
+// These values are lower bounds, as the number of bricks is computed
+const GAP_BETWEEN_BRICKS_AND_CEILING: f32 = 20.0;
+const GAP_BETWEEN_BRICKS_AND_SIDES: f32 = 20.0;
+// In versus, the bricks are this many rows across the middle of the arena
+const VERSUS_BRICK_ROWS: usize = 3;
+
+const SCOREBOARD_FONT_SIZE: f32 = 33.0;
+const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
+
+const BACKGROUND_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const PADDLE_COLOR: Color = Color::srgb(0.3, 0.3, 0.7);
+const BALL_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+const BRICK_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+const WALL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const TEXT_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+const SCORE_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+
+/// Name of the game in the launcher
+pub const NAME: &str = "breakout";
+const COLLISION_SOUND: &str = "breakout/collision";
+const MUSIC: &str = "breakout/music";
+/// The state breakout runs in
+pub const STATE: AppState = AppState::Playing(NAME);
+/// The state starting breakout, which loads its assets first
+pub const LOADING: AppState = AppState::Loading(NAME);
+
+/// The whole game, which runs while in [`STATE`]
+pub struct BreakoutPlugin;
+
+impl Plugin for BreakoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ActionMapPlugin::<BreakoutAction>::new(CONTROLS_PATH))
+            .scope_actions::<BreakoutAction>(STATE)
+            .load_assets::<BreakoutAssets>(NAME)
+            .add_sound(
+                COLLISION_SOUND,
+                Sound::sfx(&["sounds/breakout_collision.ogg"]).max_playing(2),
+            )
+            .add_sound(MUSIC, Sound::music("sounds/breakout_music.ogg").volume(0.5))
+            .play_music_in(MUSIC, STATE)
+            .add_plugins((
+                paddle_input::PaddleInputPlugin,
+                powerups::PowerUpPlugin,
+                versus::VersusPlugin,
+                autopilot::AutopilotPlugin,
+            ))
+            .init_resource::<GameRng>()
+            .insert_resource(Score(0))
+            .insert_resource(Lives(STARTING_LIVES))
+            .register_type::<Paddle>()
+            .register_type::<Ball>()
+            .register_type::<Velocity>()
+            .register_type::<Brick>()
+            .register_type::<Score>()
+            .register_type::<Lives>()
+            .add_scene_export(
+                NAME,
+                scene_types(),
+                BreakoutAction::ExportScene,
+                BreakoutAction::ImportScene,
+            )
+            .add_event::<CollisionEvent>()
+            .add_event::<BrickDestroyed>()
+            .add_event::<GameRestarted>()
+            .add_systems(OnEnter(STATE), setup)
+            // Add our gameplay simulation systems to the fixed timestep schedule
+            // which runs at 64 Hz by default
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_velocity,
+                    move_paddle,
+                    // In versus, the balls getting past a paddle are goals instead
+                    balls::check_for_lost_balls.run_if(not(versus::is_versus)),
+                    check_for_collisions,
+                    balls::check_for_ball_collisions,
+                    play_collision_sound,
+                    restart_when_out_of_lives,
+                )
+                    // `chain`ing systems together runs them in order
+                    .chain()
+                    .run_if(in_state(STATE)),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_scoreboard,
+                    balls::spawn_debug_ball,
+                    restore_imported_scene,
+                    exit_on(BreakoutAction::Exit),
+                )
+                    .run_if(in_state(STATE)),
+            );
+    }
+}
+
+impl GamePlugin for BreakoutPlugin {
+    fn title(&self) -> &'static str {
+        NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "clear the bricks with paddle and ball, or race a friend for them"
+    }
+
+    fn attract_mode(&self) -> bool {
+        true
+    }
+}
+
+/// The stepping UI, set up to debug breakout's collisions
+pub fn stepping_plugin() -> SteppingPlugin {
+    SteppingPlugin::default()
+        .add_schedule(Update)
+        .add_schedule(FixedUpdate)
+        .break_on_event::<CollisionEvent>("collision")
+        .break_on_change::<Score>("score change")
+        .inspect_component::<Transform, With<Ball>>()
+        .inspect_component::<Velocity, With<Ball>>()
+        .inspect_component::<Transform, With<Paddle>>()
+        .inspect_component::<Transform, With<TopPaddle>>()
+        .inspect_count::<With<Brick>>()
+        .inspect_resource::<Score>()
+        .inspect_resource::<Lives>()
+        .inspect_resource::<VersusScore>()
+        .rewind_component::<Transform, Or<(With<Ball>, With<Paddle>, With<TopPaddle>)>>()
+        .rewind_component::<Velocity, With<Ball>>()
+        .rewind_presence::<With<Brick>>(|commands, transform| {
+            spawn_brick(commands, transform.translation.truncate())
+        })
+        // Balls split off or lost since are put back, their velocity is restored with the rest
+        .rewind_presence::<With<Ball>>(|commands, transform| {
+            commands.spawn_ball(transform.translation, Vec2::ZERO).id()
+        })
+        .rewind_resource::<Score>()
+        .rewind_resource::<Lives>()
+        .show_hint_in(STATE)
+        .at(Val::Percent(35.0), Val::Percent(50.0))
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Paddle;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Ball;
+
+#[derive(Component, Debug, Clone, PartialEq, Deref, DerefMut, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+struct Velocity(Vec2);
+
+#[derive(Event, Default)]
+struct CollisionEvent;
+
+/// Sent whenever a brick is removed from play, so other systems (like power-up drops) can react
+#[derive(Event)]
+struct BrickDestroyed {
+    position: Vec2,
+    /// The side which broke it, if a paddle touched the ball which did
+    owner: Option<Side>,
+}
+
+/// Sent when a new game starts after the last life was lost, so the rest of the last game can be
+/// cleared away
+#[derive(Event, Default)]
+struct GameRestarted;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Brick;
+
+// Default must be implemented to define this as a required component for the Wall component below
+#[derive(Component, Default)]
+struct Collider;
+
+// This is a collection of the components that define a "Wall" in our game
+#[derive(Component)]
+#[require(Sprite, Transform, Collider)]
+struct Wall;
+
+/// Which side of the arena is this wall located on?
+enum WallLocation {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+impl WallLocation {
+    /// Location of the *center* of the wall, used in `transform.translation()`
+    fn position(&self) -> Vec2 {
+        match self {
+            WallLocation::Left => Vec2::new(LEFT_WALL, 0.),
+            WallLocation::Right => Vec2::new(RIGHT_WALL, 0.),
+            WallLocation::Bottom => Vec2::new(0., BOTTOM_WALL),
+            WallLocation::Top => Vec2::new(0., TOP_WALL),
+        }
+    }
+
+    /// (x, y) dimensions of the wall, used in `transform.scale()`
+    fn size(&self) -> Vec2 {
+        let arena_height = TOP_WALL - BOTTOM_WALL;
+        let arena_width = RIGHT_WALL - LEFT_WALL;
+        // Make sure we haven't messed up our constants
+        assert!(arena_height > 0.0);
+        assert!(arena_width > 0.0);
+
+        match self {
+            WallLocation::Left | WallLocation::Right => {
+                Vec2::new(WALL_THICKNESS, arena_height + WALL_THICKNESS)
+            }
+            WallLocation::Bottom | WallLocation::Top => {
+                Vec2::new(arena_width + WALL_THICKNESS, WALL_THICKNESS)
+            }
+        }
+    }
+}
+
+impl Wall {
+    // This "builder method" allows us to reuse logic across our wall entities,
+    // making our code easier to read and less prone to bugs when we change the logic
+    // Notice the use of Sprite and Transform alongside Wall, overwriting the default values defined for the required components
+    fn new(location: WallLocation) -> (Wall, Sprite, Transform) {
+        (
+            Wall,
+            Sprite::from_color(WALL_COLOR, Vec2::ONE),
+            Transform {
+                // We need to convert our Vec2 into a Vec3, by giving it a z-coordinate
+                // This is used to determine the order of our sprites
+                translation: location.position().extend(0.0),
+                // The z-scale of 2D objects must always be 1.0,
+                // or their ordering will be affected in surprising ways.
+                // See https://github.com/bevyengine/bevy/issues/4149
+                scale: location.size().extend(1.0),
+                ..default()
+            },
+        )
+    }
+}
+
+// This resource tracks the game's score
+#[derive(Resource, Debug, Clone, PartialEq, Deref, DerefMut, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+struct Score(usize);
+
+// This resource tracks how many lives the player has left
+#[derive(Resource, Debug, Clone, PartialEq, Deref, DerefMut, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+struct Lives(usize);
+
+#[derive(Component)]
+struct ScoreboardUi;
+
+// Add the game's entities to our world
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    assets: Res<BreakoutAssets>,
+    mode: Res<BreakoutMode>,
+) {
+    // Camera
+    commands.spawn((Camera2d, StateScoped(STATE)));
+    commands.insert_resource(ClearColor(BACKGROUND_COLOR));
+
+    // Start each game afresh
+    commands.insert_resource(Score(0));
+    commands.insert_resource(Lives(STARTING_LIVES));
+
+    // Paddle
+    let paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR;
+
+    commands.spawn((
+        Sprite::from_color(PADDLE_COLOR, Vec2::ONE),
+        Transform {
+            translation: Vec3::new(0.0, paddle_y, 0.0),
+            scale: PADDLE_SIZE.extend(1.0),
+            ..default()
+        },
+        Paddle,
+        Side::Bottom,
+        Collider,
+        StateScoped(STATE),
+    ));
+
+    // Ball
+    commands.insert_resource(BallAssets {
+        mesh: meshes.add(Circle::default()),
+        material: materials.add(BALL_COLOR),
+    });
+    match *mode {
+        BreakoutMode::Classic => {
+            commands.spawn_ball(
+                BALL_STARTING_POSITION,
+                INITIAL_BALL_DIRECTION.normalize() * BALL_SPEED,
+            );
+        }
+        // The classic starting position is among the bricks
+        BreakoutMode::Versus => versus::serve(&mut commands, Side::Bottom),
+    }
+
+    // Scoreboard, showing both sides' points in versus
+    let (score_label, lives_label) = match *mode {
+        BreakoutMode::Classic => ("Score: ", "  Lives: "),
+        BreakoutMode::Versus => ("Bottom: ", "  Top: "),
+    };
+    let font = TextFont {
+        font: assets.font.clone(),
+        font_size: SCOREBOARD_FONT_SIZE,
+        ..default()
+    };
+    commands.spawn((
+        Text::new(score_label),
+        font.clone(),
+        TextColor(TEXT_COLOR),
+        ScoreboardUi,
+        StateScoped(STATE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: SCOREBOARD_TEXT_PADDING,
+            left: SCOREBOARD_TEXT_PADDING,
+            ..default()
+        },
+        children![
+            (TextSpan::default(), font.clone(), TextColor(SCORE_COLOR),),
+            (
+                TextSpan::new(lives_label),
+                font.clone(),
+                TextColor(TEXT_COLOR),
+            ),
+            (TextSpan::default(), font.clone(), TextColor(SCORE_COLOR),)
+        ],
+    ));
+
+    // Walls
+    commands.spawn((Wall::new(WallLocation::Left), StateScoped(STATE)));
+    commands.spawn((Wall::new(WallLocation::Right), StateScoped(STATE)));
+    commands.spawn((Wall::new(WallLocation::Bottom), StateScoped(STATE)));
+    commands.spawn((Wall::new(WallLocation::Top), StateScoped(STATE)));
+
+    // Bricks
+    spawn_bricks(&mut commands, *mode);
+}
+
+fn spawn_bricks(commands: &mut Commands, mode: BreakoutMode) {
+    let paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR;
+    let total_width_of_bricks = (RIGHT_WALL - LEFT_WALL) - 2. * GAP_BETWEEN_BRICKS_AND_SIDES;
+    assert!(total_width_of_bricks > 0.0);
+
+    // Given the space available, compute how many rows and columns of bricks we can fit
+    let n_columns = (total_width_of_bricks / (BRICK_SIZE.x + GAP_BETWEEN_BRICKS)).floor() as usize;
+    let (bottom_edge_of_bricks, n_rows) = match mode {
+        BreakoutMode::Classic => {
+            let bottom_edge_of_bricks = paddle_y + GAP_BETWEEN_PADDLE_AND_BRICKS;
+            let total_height_of_bricks =
+                TOP_WALL - bottom_edge_of_bricks - GAP_BETWEEN_BRICKS_AND_CEILING;
+            assert!(total_height_of_bricks > 0.0);
+            let n_rows =
+                (total_height_of_bricks / (BRICK_SIZE.y + GAP_BETWEEN_BRICKS)).floor() as usize;
+            (bottom_edge_of_bricks, n_rows)
+        }
+        // A band centered between the two paddles
+        BreakoutMode::Versus => {
+            let total_height_of_bricks =
+                VERSUS_BRICK_ROWS as f32 * (BRICK_SIZE.y + GAP_BETWEEN_BRICKS) - GAP_BETWEEN_BRICKS;
+            (-total_height_of_bricks / 2.0, VERSUS_BRICK_ROWS)
+        }
+    };
+    let n_vertical_gaps = n_columns - 1;
+
+    // Because we need to round the number of columns,
+    // the space on the top and sides of the bricks only captures a lower bound, not an exact value
+    let center_of_bricks = (LEFT_WALL + RIGHT_WALL) / 2.0;
+    let left_edge_of_bricks = center_of_bricks
+        // Space taken up by the bricks
+        - (n_columns as f32 / 2.0 * BRICK_SIZE.x)
+        // Space taken up by the gaps
+        - n_vertical_gaps as f32 / 2.0 * GAP_BETWEEN_BRICKS;
+
+    // In Bevy, the `translation` of an entity describes the center point,
+    // not its bottom-left corner
+    let offset_x = left_edge_of_bricks + BRICK_SIZE.x / 2.;
+    let offset_y = bottom_edge_of_bricks + BRICK_SIZE.y / 2.;
+
+    for row in 0..n_rows {
+        for column in 0..n_columns {
+            let brick_position = Vec2::new(
+                offset_x + column as f32 * (BRICK_SIZE.x + GAP_BETWEEN_BRICKS),
+                offset_y + row as f32 * (BRICK_SIZE.y + GAP_BETWEEN_BRICKS),
+            );
+
+            spawn_brick(commands, brick_position);
+        }
+    }
+}
+
+fn spawn_brick(commands: &mut Commands, brick_position: Vec2) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                color: BRICK_COLOR,
+                ..default()
+            },
+            Transform {
+                translation: brick_position.extend(0.0),
+                scale: Vec3::new(BRICK_SIZE.x, BRICK_SIZE.y, 1.0),
+                ..default()
+            },
+            Brick,
+            Collider,
+            StateScoped(STATE),
+        ))
+        .id()
+}
+
+/// What the exported scenes hold: the paddles, balls and bricks, with the score and lives and
+/// the versus points. Sprites, meshes and colliders are added back by [`restore_imported_scene`].
+fn scene_types() -> SceneTypes {
+    SceneTypes::default()
+        .entity::<Paddle>()
+        .entity::<TopPaddle>()
+        .entity::<Ball>()
+        .entity::<Brick>()
+        .component::<Transform>()
+        .component::<Velocity>()
+        .component::<Side>()
+        .resource::<Score>()
+        .resource::<Lives>()
+        .resource::<BreakoutMode>()
+        .resource::<VersusScore>()
+}
+
+fn restore_imported_scene(
+    mut commands: Commands,
+    mut scene_imported: EventReader<SceneImported>,
+    kinds: Query<(Has<Paddle>, Has<TopPaddle>, Has<Ball>)>,
+    ball_assets: Res<BallAssets>,
+) {
+    for event in scene_imported.read().filter(|event| event.game == NAME) {
+        for &entity in &event.entities {
+            let Ok((is_paddle, is_top_paddle, is_ball)) = kinds.get(entity) else {
+                continue;
+            };
+            if is_top_paddle {
+                commands.entity(entity).insert(versus::top_paddle_visuals());
+            } else if is_ball {
+                commands.entity(entity).insert((
+                    Mesh2d(ball_assets.mesh.clone()),
+                    MeshMaterial2d(ball_assets.material.clone()),
+                ));
+            } else {
+                let color = if is_paddle { PADDLE_COLOR } else { BRICK_COLOR };
+                commands
+                    .entity(entity)
+                    .insert((Sprite::from_color(color, Vec2::ONE), Collider));
+            }
+        }
+    }
+}
+
+fn move_paddle(
+    intent: Res<PaddleIntent>,
+    mut paddle_transform: Single<&mut Transform, With<Paddle>>,
+    time: Res<Time>,
+) {
+    // Calculate the new horizontal paddle position based on player input,
+    // either placing the paddle directly under the pointer or moving it along the input axis
+    let new_paddle_position = match intent.target_x {
+        Some(target_x) => target_x,
+        None => paddle_transform.translation.x + intent.axis * PADDLE_SPEED * time.delta_secs(),
+    };
+
+    // Update the paddle position,
+    // making sure it doesn't cause the paddle to leave the arena.
+    // The paddle width is read from its scale, since power-ups can widen it
+    paddle_transform.translation.x =
+        clamp_paddle_x(new_paddle_position, paddle_transform.scale.x / 2.0);
+}
+
+/// `x` kept far enough from the walls for a paddle `half_paddle_width` wide
+fn clamp_paddle_x(x: f32, half_paddle_width: f32) -> f32 {
+    let left_bound = LEFT_WALL + WALL_THICKNESS / 2.0 + half_paddle_width + PADDLE_PADDING;
+    let right_bound = RIGHT_WALL - WALL_THICKNESS / 2.0 - half_paddle_width - PADDLE_PADDING;
+    x.clamp(left_bound, right_bound)
+}
+
+fn apply_velocity(mut query: Query<(&mut Transform, &Velocity)>, time: Res<Time>) {
+    for (mut transform, velocity) in &mut query {
+        transform.translation.x += velocity.x * time.delta_secs();
+        transform.translation.y += velocity.y * time.delta_secs();
+    }
+}
+
+fn update_scoreboard(
+    score: Res<Score>,
+    lives: Res<Lives>,
+    mode: Res<BreakoutMode>,
+    versus_score: Res<VersusScore>,
+    score_root: Single<Entity, (With<ScoreboardUi>, With<Text>)>,
+    mut writer: TextUiWriter,
+) {
+    let (first, second) = match *mode {
+        BreakoutMode::Classic => (**score, **lives),
+        BreakoutMode::Versus => (versus_score.bottom, versus_score.top),
+    };
+    *writer.text(*score_root, 1) = first.to_string();
+    *writer.text(*score_root, 3) = second.to_string();
+}
+
+#[allow(clippy::type_complexity)]
+fn check_for_collisions(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut ball_query: Query<(Entity, &mut Velocity, &Transform, Option<&LastTouch>), With<Ball>>,
+    collider_query: Query<(Entity, &Transform, Option<&Brick>, Option<&Side>), With<Collider>>,
+    mut collision_events: EventWriter<CollisionEvent>,
+    mut brick_events: EventWriter<BrickDestroyed>,
+) {
+    // Several balls can hit the same brick during one tick, but it should only be scored once
+    let mut destroyed_bricks = Vec::new();
+
+    for (ball, mut ball_velocity, ball_transform, last_touch) in &mut ball_query {
+        // Bricks score for the side which touched the ball last
+        let mut owner = last_touch.map(|last_touch| last_touch.0);
+        for (collider_entity, collider_transform, maybe_brick, maybe_side) in &collider_query {
+            let collision = ball_collision(
+                BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.),
+                Aabb2d::new(
+                    collider_transform.translation.truncate(),
+                    collider_transform.scale.truncate() / 2.,
+                ),
+            );
+
+            if let Some(collision) = collision {
+                // Writes a collision event so that other systems can react to the collision
+                collision_events.write_default();
+
+                if let Some(&side) = maybe_side {
+                    owner = Some(side);
+                    commands.entity(ball).insert(LastTouch(side));
+                }
+
+                // Bricks should be despawned and increment the scoreboard on collision
+                if maybe_brick.is_some() && !destroyed_bricks.contains(&collider_entity) {
+                    destroyed_bricks.push(collider_entity);
+                    commands.entity(collider_entity).despawn();
+                    **score += 1;
+                    brick_events.write(BrickDestroyed {
+                        position: collider_transform.translation.truncate(),
+                        owner,
+                    });
+                }
+
+                // Reflect the ball's velocity when it collides
+                let mut reflect_x = false;
+                let mut reflect_y = false;
+
+                // Reflect only if the velocity is in the opposite direction of the collision
+                // This prevents the ball from getting stuck inside the bar
+                match collision {
+                    Collision::Left => reflect_x = ball_velocity.x > 0.0,
+                    Collision::Right => reflect_x = ball_velocity.x < 0.0,
+                    Collision::Top => reflect_y = ball_velocity.y < 0.0,
+                    Collision::Bottom => reflect_y = ball_velocity.y > 0.0,
+                }
+
+                // Reflect velocity on the x-axis if we hit something on the x-axis
+                if reflect_x {
+                    ball_velocity.x = -ball_velocity.x;
+                }
+
+                // Reflect velocity on the y-axis if we hit something on the y-axis
+                if reflect_y {
+                    ball_velocity.y = -ball_velocity.y;
+                }
+            }
+        }
+    }
+}
+
+// Once the last life is lost, start a new game with a fresh wall of bricks and a single ball
+fn restart_when_out_of_lives(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut lives: ResMut<Lives>,
+    brick_query: Query<Entity, With<Brick>>,
+    ball_query: Query<Entity, With<Ball>>,
+    mut restart_events: EventWriter<GameRestarted>,
+) {
+    if **lives > 0 {
+        return;
+    }
+
+    info!("Game over! Final score: {}", **score);
+    for entity in brick_query.iter().chain(&ball_query) {
+        commands.entity(entity).despawn();
+    }
+    spawn_bricks(&mut commands, BreakoutMode::Classic);
+    commands.spawn_ball(
+        BALL_STARTING_POSITION,
+        INITIAL_BALL_DIRECTION.normalize() * BALL_SPEED,
+    );
+    **score = 0;
+    **lives = STARTING_LIVES;
+    // The power-ups of the last game are cleared by `powerups`
+    restart_events.write_default();
+}
+
+fn play_collision_sound(mut commands: Commands, mut collision_events: EventReader<CollisionEvent>) {
+    // Play a sound once per frame if a collision occurred.
+    if !collision_events.is_empty() {
+        // This prevents events staying active on the next frame.
+        collision_events.clear();
+        commands.play_sound(COLLISION_SOUND);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Collision {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+// Returns `Some` if `ball` collides with `bounding_box`.
+// The returned `Collision` is the side of `bounding_box` that `ball` hit.
+fn ball_collision(ball: BoundingCircle, bounding_box: Aabb2d) -> Option<Collision> {
+    if !ball.intersects(&bounding_box) {
+        return None;
+    }
+
+    let closest = bounding_box.closest_point(ball.center());
+    let offset = ball.center() - closest;
+    let side = if offset.x.abs() > offset.y.abs() {
+        if offset.x < 0. {
+            Collision::Left
+        } else {
+            Collision::Right
+        }
+    } else if offset.y > 0. {
+        Collision::Top
+    } else {
+        Collision::Bottom
+    };
+
+    Some(side)
+}
@@ -0,0 +1,132 @@
+//! Device independent paddle controls.
+//!
+//! The bound [`BreakoutAction`]s, mouse and touch input are all turned into a [`PaddleIntent`]
+//! before the fixed timestep runs, so the gameplay systems never need to know which device is in use.
+
+use action_map::ActionState;
+use bevy::{input::InputSystem, prelude::*, window::PrimaryWindow};
+
+use crate::{STATE, actions::BreakoutAction};
+
+/// Plugin translating input devices into a [`PaddleIntent`]
+pub struct PaddleInputPlugin;
+
+impl Plugin for PaddleInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PaddleIntent>()
+            .init_resource::<PaddleInputSettings>()
+            .add_systems(
+                PreUpdate,
+                (
+                    read_actions,
+                    // Moving the mouse or touching the screen takes over from the buttons
+                    read_pointer,
+                )
+                    .chain()
+                    .after(InputSystem)
+                    .run_if(in_state(STATE)),
+            )
+            .add_systems(FixedPostUpdate, consume_launch);
+    }
+}
+
+/// What the player wants the paddle to do, whichever device they are using
+#[derive(Resource, Debug, Default)]
+pub struct PaddleIntent {
+    /// Direction to move the paddle in, from -1.0 (full speed left) to 1.0 (full speed right)
+    pub axis: f32,
+    /// Where to place the center of the paddle when using absolute positioning, in world units.
+    ///
+    /// This is cleared as soon as the player moves the paddle with the keyboard or a gamepad.
+    pub target_x: Option<f32>,
+    /// Whether the player asked to launch balls stuck to the paddle.
+    ///
+    /// Frames can pass without a fixed tick, so this stays set until the next tick has run.
+    pub launch: bool,
+}
+
+/// Tuning of the analog paddle controls
+#[derive(Resource, Debug)]
+pub struct PaddleInputSettings {
+    /// Stick deflections smaller than this are ignored, to keep a worn stick from drifting
+    pub dead_zone: f32,
+    /// Multiplier applied to the stick after the dead zone is removed
+    pub sensitivity: f32,
+    /// Whether the mouse and touch screen can position the paddle
+    pub pointer_enabled: bool,
+}
+
+impl Default for PaddleInputSettings {
+    fn default() -> Self {
+        PaddleInputSettings {
+            dead_zone: 0.15,
+            sensitivity: 1.0,
+            pointer_enabled: true,
+        }
+    }
+}
+
+impl PaddleInputSettings {
+    /// Remove the dead zone from a raw stick value, rescaling the rest of its travel to 0..=1
+    fn apply_dead_zone(&self, value: f32) -> f32 {
+        let magnitude = value.abs();
+        if magnitude <= self.dead_zone {
+            return 0.0;
+        }
+
+        let scaled = (magnitude - self.dead_zone) / (1.0 - self.dead_zone).max(f32::EPSILON);
+        (scaled * self.sensitivity).min(1.0).copysign(value)
+    }
+}
+
+fn read_actions(
+    actions: Res<ActionState<BreakoutAction>>,
+    settings: Res<PaddleInputSettings>,
+    mut intent: ResMut<PaddleIntent>,
+) {
+    // Keys are either fully pressed or not, so the dead zone only affects analog inputs
+    let axis = settings.apply_dead_zone(actions.value(BreakoutAction::PaddleRight))
+        - settings.apply_dead_zone(actions.value(BreakoutAction::PaddleLeft));
+
+    intent.axis = axis.clamp(-1.0, 1.0);
+    intent.launch |= actions.pressed(BreakoutAction::Launch);
+    if intent.axis != 0.0 {
+        intent.target_x = None;
+    }
+}
+
+fn read_pointer(
+    mut cursor_events: EventReader<CursorMoved>,
+    touches: Res<Touches>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+    settings: Res<PaddleInputSettings>,
+    mut intent: ResMut<PaddleIntent>,
+) {
+    if !settings.pointer_enabled {
+        cursor_events.clear();
+        return;
+    }
+
+    // Only follow the mouse once it has moved, so it doesn't fight with the other devices
+    let mouse_moved = cursor_events.read().count() > 0;
+    let pointer_position = match touches.first_pressed_position() {
+        Some(touch_position) => Some(touch_position),
+        None if mouse_moved => window.cursor_position(),
+        None => None,
+    };
+
+    let (camera, camera_transform) = *camera_query;
+    if let Some(pointer_position) = pointer_position
+        && let Ok(world_position) = camera.viewport_to_world_2d(camera_transform, pointer_position)
+    {
+        intent.target_x = Some(world_position.x);
+    }
+
+    intent.launch |= touches.any_just_pressed();
+}
+
+/// The launch was seen by the fixed tick which just ran
+fn consume_launch(mut intent: ResMut<PaddleIntent>) {
+    intent.launch = false;
+}
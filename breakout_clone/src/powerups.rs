@@ -0,0 +1,490 @@
+//! Power-up capsules that occasionally drop from destroyed bricks.
+//!
+//! Catching a capsule with the paddle applies its effect. Timed effects are stored as components
+//! holding a [`Timer`] on the paddle or on the balls, and are undone when that timer finishes.
+
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{
+    BALL_DIAMETER, BALL_SPEED, BOTTOM_WALL, Ball, Brick, BrickDestroyed, GameRestarted, Lives,
+    PADDLE_SIZE, Paddle, STATE, Score, TOP_WALL, Velocity, ball_collision,
+    balls::SpawnBallExt,
+    check_for_collisions,
+    paddle_input::PaddleIntent,
+    rng::GameRng,
+    versus::{Side, is_versus},
+};
+
+// Chance that a destroyed brick drops a capsule
+const POWER_UP_DROP_CHANCE: f32 = 0.2;
+const CAPSULE_SIZE: Vec2 = Vec2::new(40.0, 16.0);
+const CAPSULE_SPEED: f32 = 150.0;
+
+// How long each timed power-up lasts, in seconds
+const WIDE_PADDLE_DURATION: f32 = 10.0;
+const STICKY_PADDLE_DURATION: f32 = 10.0;
+const LASER_DURATION: f32 = 8.0;
+const SLOW_BALL_DURATION: f32 = 8.0;
+
+const WIDE_PADDLE_FACTOR: f32 = 1.5;
+const SLOW_BALL_FACTOR: f32 = 0.6;
+// Extra balls leave the ball that spawned them at these angles, in radians
+const MULTI_BALL_SPREAD: f32 = 0.4;
+// A ball stuck to the paddle is launched on its own after this many seconds
+const STICKY_RELEASE_TIME: f32 = 3.0;
+
+const LASER_SIZE: Vec2 = Vec2::new(4.0, 16.0);
+const LASER_SPEED: f32 = 600.0;
+const LASER_FIRE_INTERVAL: f32 = 0.4;
+
+const LASER_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
+
+/// Plugin dropping, collecting and expiring power-ups
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                clear_power_ups,
+                drop_power_ups,
+                collect_power_ups,
+                stick_balls_to_paddle,
+                move_stuck_balls,
+                release_stuck_balls,
+                fire_lasers,
+                check_for_laser_collisions,
+                expire_power_ups,
+            )
+                .chain()
+                .after(check_for_collisions)
+                // They would only help the bottom paddle in versus
+                .run_if(in_state(STATE).and(not(is_versus))),
+        );
+    }
+}
+
+/// The effect applied when a capsule is caught
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpKind {
+    MultiBall,
+    WidePaddle,
+    StickyPaddle,
+    Laser,
+    SlowBall,
+    ExtraLife,
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::MultiBall,
+        PowerUpKind::WidePaddle,
+        PowerUpKind::StickyPaddle,
+        PowerUpKind::Laser,
+        PowerUpKind::SlowBall,
+        PowerUpKind::ExtraLife,
+    ];
+
+    fn random(rng: &mut GameRng) -> PowerUpKind {
+        PowerUpKind::ALL[rng.random_range(0..PowerUpKind::ALL.len())]
+    }
+
+    /// Capsules are color coded so the player can tell them apart
+    fn color(&self) -> Color {
+        match self {
+            PowerUpKind::MultiBall => Color::srgb(1.0, 0.5, 0.5),
+            PowerUpKind::WidePaddle => Color::srgb(0.3, 0.3, 0.7),
+            PowerUpKind::StickyPaddle => Color::srgb(0.3, 0.7, 0.3),
+            PowerUpKind::Laser => LASER_COLOR,
+            PowerUpKind::SlowBall => Color::srgb(0.9, 0.7, 0.2),
+            PowerUpKind::ExtraLife => Color::srgb(0.7, 0.3, 0.7),
+        }
+    }
+}
+
+/// A falling capsule which applies its power-up when it touches the paddle
+#[derive(Component)]
+struct PowerUpCapsule(PowerUpKind);
+
+/// The paddle is wider than [`PADDLE_SIZE`] until the timer finishes
+#[derive(Component, Deref, DerefMut)]
+struct WidePaddle(Timer);
+
+/// Balls hitting the paddle stick to it until launched, until the timer finishes
+#[derive(Component, Deref, DerefMut)]
+struct StickyPaddle(Timer);
+
+/// The paddle fires lasers upwards until `duration` finishes
+#[derive(Component)]
+struct LaserPaddle {
+    duration: Timer,
+    cooldown: Timer,
+}
+
+/// The ball moves at a fraction of [`BALL_SPEED`] until the timer finishes
+#[derive(Component, Deref, DerefMut)]
+struct SlowBall(Timer);
+
+/// A ball held by a sticky paddle.
+///
+/// The ball's [`Velocity`] is removed while it is stuck, so it is neither moved nor collided,
+/// and is given back when the ball is launched.
+#[derive(Component)]
+struct StuckToPaddle {
+    offset: f32,
+    velocity: Vec2,
+    release: Timer,
+}
+
+#[derive(Component)]
+struct Laser;
+
+/// Nothing of the last game carries over into a new one: capsules still falling and lasers still
+/// flying are removed, and the paddle loses its power-ups
+#[allow(clippy::type_complexity)]
+fn clear_power_ups(
+    mut commands: Commands,
+    mut restart_events: EventReader<GameRestarted>,
+    leftover_query: Query<Entity, Or<(With<PowerUpCapsule>, With<Laser>)>>,
+    paddle_query: Single<(Entity, &mut Transform), With<Paddle>>,
+) {
+    if restart_events.read().count() == 0 {
+        return;
+    }
+
+    for entity in &leftover_query {
+        commands.entity(entity).despawn();
+    }
+    let (paddle, mut paddle_transform) = paddle_query.into_inner();
+    paddle_transform.scale.x = PADDLE_SIZE.x;
+    commands
+        .entity(paddle)
+        .remove::<(WidePaddle, StickyPaddle, LaserPaddle)>();
+}
+
+fn drop_power_ups(
+    mut commands: Commands,
+    mut brick_events: EventReader<BrickDestroyed>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in brick_events.read() {
+        if rng.random::<f32>() >= POWER_UP_DROP_CHANCE {
+            continue;
+        }
+
+        let kind = PowerUpKind::random(&mut rng);
+        commands.spawn((
+            Sprite::from_color(kind.color(), Vec2::ONE),
+            Transform {
+                // Draw capsules above the bricks they fall past
+                translation: event.position.extend(1.0),
+                scale: CAPSULE_SIZE.extend(1.0),
+                ..default()
+            },
+            Velocity(Vec2::new(0.0, -CAPSULE_SPEED)),
+            PowerUpCapsule(kind),
+            StateScoped(STATE),
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn collect_power_ups(
+    mut commands: Commands,
+    capsule_query: Query<(Entity, &Transform, &PowerUpCapsule), Without<Paddle>>,
+    paddle_query: Single<(Entity, &mut Transform), (With<Paddle>, Without<Ball>)>,
+    ball_query: Query<(Entity, &Transform, &Velocity, Option<&SlowBall>), With<Ball>>,
+    mut lives: ResMut<Lives>,
+) {
+    let (paddle, mut paddle_transform) = paddle_query.into_inner();
+    let paddle_box = Aabb2d::new(
+        paddle_transform.translation.truncate(),
+        paddle_transform.scale.truncate() / 2.,
+    );
+
+    for (capsule, capsule_transform, PowerUpCapsule(kind)) in &capsule_query {
+        let capsule_box = Aabb2d::new(
+            capsule_transform.translation.truncate(),
+            capsule_transform.scale.truncate() / 2.,
+        );
+
+        // Capsules the paddle missed are removed once they leave the arena
+        if capsule_box.max.y < BOTTOM_WALL {
+            commands.entity(capsule).despawn();
+            continue;
+        }
+
+        if !capsule_box.intersects(&paddle_box) {
+            continue;
+        }
+
+        commands.entity(capsule).despawn();
+        match kind {
+            PowerUpKind::MultiBall => {
+                for (_, ball_transform, velocity, maybe_slow) in &ball_query {
+                    for angle in [-MULTI_BALL_SPREAD, MULTI_BALL_SPREAD] {
+                        let mut new_ball = commands.spawn_ball(
+                            ball_transform.translation,
+                            Vec2::from_angle(angle).rotate(**velocity),
+                        );
+                        // New balls share the slowdown of the ball they split from
+                        if let Some(slow) = maybe_slow {
+                            new_ball.insert(SlowBall(slow.0.clone()));
+                        }
+                    }
+                }
+            }
+            PowerUpKind::WidePaddle => {
+                paddle_transform.scale.x = PADDLE_SIZE.x * WIDE_PADDLE_FACTOR;
+                commands
+                    .entity(paddle)
+                    .insert(WidePaddle(Timer::from_seconds(
+                        WIDE_PADDLE_DURATION,
+                        TimerMode::Once,
+                    )));
+            }
+            PowerUpKind::StickyPaddle => {
+                commands
+                    .entity(paddle)
+                    .insert(StickyPaddle(Timer::from_seconds(
+                        STICKY_PADDLE_DURATION,
+                        TimerMode::Once,
+                    )));
+            }
+            PowerUpKind::Laser => {
+                commands.entity(paddle).insert(LaserPaddle {
+                    duration: Timer::from_seconds(LASER_DURATION, TimerMode::Once),
+                    cooldown: Timer::from_seconds(LASER_FIRE_INTERVAL, TimerMode::Repeating),
+                });
+            }
+            PowerUpKind::SlowBall => {
+                for (ball, _, velocity, maybe_slow) in &ball_query {
+                    // Only slow balls down once, catching another capsule just extends the effect
+                    if maybe_slow.is_none() {
+                        commands
+                            .entity(ball)
+                            .insert(Velocity(**velocity * SLOW_BALL_FACTOR));
+                    }
+                    commands.entity(ball).insert(SlowBall(Timer::from_seconds(
+                        SLOW_BALL_DURATION,
+                        TimerMode::Once,
+                    )));
+                }
+            }
+            PowerUpKind::ExtraLife => {
+                **lives += 1;
+            }
+        }
+    }
+}
+
+fn stick_balls_to_paddle(
+    mut commands: Commands,
+    paddle_query: Single<&Transform, (With<Paddle>, With<StickyPaddle>)>,
+    ball_query: Query<(Entity, &Transform, &Velocity), With<Ball>>,
+) {
+    let paddle_transform = *paddle_query;
+    let paddle_box = Aabb2d::new(
+        paddle_transform.translation.truncate(),
+        paddle_transform.scale.truncate() / 2.,
+    );
+
+    for (ball, ball_transform, velocity) in &ball_query {
+        let ball_circle =
+            BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.);
+
+        // The ball has already been reflected off the paddle this tick,
+        // so a ball touching the paddle while moving upwards has just bounced off it
+        if velocity.y > 0.0 && ball_collision(ball_circle, paddle_box).is_some() {
+            commands
+                .entity(ball)
+                .remove::<Velocity>()
+                .insert(StuckToPaddle {
+                    offset: ball_transform.translation.x - paddle_transform.translation.x,
+                    velocity: **velocity,
+                    release: Timer::from_seconds(STICKY_RELEASE_TIME, TimerMode::Once),
+                });
+        }
+    }
+}
+
+fn move_stuck_balls(
+    paddle_query: Single<&Transform, With<Paddle>>,
+    mut ball_query: Query<(&mut Transform, &StuckToPaddle), Without<Paddle>>,
+) {
+    let paddle_transform = *paddle_query;
+    let ball_y =
+        paddle_transform.translation.y + paddle_transform.scale.y / 2.0 + BALL_DIAMETER / 2.0;
+
+    for (mut ball_transform, stuck) in &mut ball_query {
+        // Keep the ball inside the paddle, even if the paddle shrank after the ball stuck to it
+        let half_width = paddle_transform.scale.x / 2.0;
+        let offset = stuck.offset.clamp(-half_width, half_width);
+        ball_transform.translation.x = paddle_transform.translation.x + offset;
+        ball_transform.translation.y = ball_y;
+    }
+}
+
+fn release_stuck_balls(
+    mut commands: Commands,
+    intent: Res<PaddleIntent>,
+    mut ball_query: Query<(Entity, &mut StuckToPaddle)>,
+    time: Res<Time>,
+) {
+    for (ball, mut stuck) in &mut ball_query {
+        if intent.launch || stuck.release.tick(time.delta()).finished() {
+            commands
+                .entity(ball)
+                .remove::<StuckToPaddle>()
+                .insert(Velocity(stuck.velocity));
+        }
+    }
+}
+
+fn fire_lasers(
+    mut commands: Commands,
+    paddle_query: Single<(&Transform, &mut LaserPaddle)>,
+    time: Res<Time>,
+) {
+    let (paddle_transform, mut laser_paddle) = paddle_query.into_inner();
+    if !laser_paddle.cooldown.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // One laser from each end of the paddle
+    let half_width = paddle_transform.scale.x / 2.0 - LASER_SIZE.x;
+    for x in [-half_width, half_width] {
+        commands.spawn((
+            Sprite::from_color(LASER_COLOR, Vec2::ONE),
+            Transform {
+                translation: paddle_transform.translation + Vec3::new(x, 0.0, 1.0),
+                scale: LASER_SIZE.extend(1.0),
+                ..default()
+            },
+            Velocity(Vec2::new(0.0, LASER_SPEED)),
+            Laser,
+            StateScoped(STATE),
+        ));
+    }
+}
+
+fn check_for_laser_collisions(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    laser_query: Query<(Entity, &Transform), With<Laser>>,
+    brick_query: Query<(Entity, &Transform), With<Brick>>,
+    mut brick_events: EventWriter<BrickDestroyed>,
+) {
+    let mut destroyed_bricks = Vec::new();
+
+    for (laser, laser_transform) in &laser_query {
+        let laser_box = Aabb2d::new(
+            laser_transform.translation.truncate(),
+            laser_transform.scale.truncate() / 2.,
+        );
+
+        if laser_box.min.y > TOP_WALL {
+            commands.entity(laser).despawn();
+            continue;
+        }
+
+        let hit = brick_query.iter().find(|(brick, brick_transform)| {
+            !destroyed_bricks.contains(brick)
+                && laser_box.intersects(&Aabb2d::new(
+                    brick_transform.translation.truncate(),
+                    brick_transform.scale.truncate() / 2.,
+                ))
+        });
+
+        // A laser destroys the first brick it touches, and is used up doing so
+        if let Some((brick, brick_transform)) = hit {
+            destroyed_bricks.push(brick);
+            commands.entity(brick).despawn();
+            commands.entity(laser).despawn();
+            **score += 1;
+            // Only the bottom paddle catches power-ups
+            brick_events.write(BrickDestroyed {
+                position: brick_transform.translation.truncate(),
+                owner: Some(Side::Bottom),
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn expire_power_ups(
+    mut commands: Commands,
+    paddle_query: Single<
+        (
+            Entity,
+            &mut Transform,
+            Option<&mut WidePaddle>,
+            Option<&mut StickyPaddle>,
+            Option<&mut LaserPaddle>,
+        ),
+        With<Paddle>,
+    >,
+    mut ball_query: Query<
+        (
+            Entity,
+            Option<&mut SlowBall>,
+            Option<&mut Velocity>,
+            Option<&mut StuckToPaddle>,
+        ),
+        With<Ball>,
+    >,
+    time: Res<Time>,
+) {
+    let (paddle, mut paddle_transform, wide, sticky, laser) = paddle_query.into_inner();
+
+    if let Some(mut wide) = wide
+        && wide.tick(time.delta()).finished()
+    {
+        paddle_transform.scale.x = PADDLE_SIZE.x;
+        commands.entity(paddle).remove::<WidePaddle>();
+    }
+
+    let mut sticky_expired = false;
+    if let Some(mut sticky) = sticky
+        && sticky.tick(time.delta()).finished()
+    {
+        commands.entity(paddle).remove::<StickyPaddle>();
+        sticky_expired = true;
+    }
+
+    if let Some(mut laser) = laser
+        && laser.duration.tick(time.delta()).finished()
+    {
+        commands.entity(paddle).remove::<LaserPaddle>();
+    }
+
+    for (ball, slow, velocity, mut stuck) in &mut ball_query {
+        if let Some(mut slow) = slow
+            && slow.tick(time.delta()).finished()
+        {
+            commands.entity(ball).remove::<SlowBall>();
+            // A stuck ball keeps its velocity aside until it is launched
+            if let Some(mut velocity) = velocity {
+                **velocity = velocity.normalize_or_zero() * BALL_SPEED;
+            }
+            if let Some(stuck) = stuck.as_deref_mut() {
+                stuck.velocity = stuck.velocity.normalize_or_zero() * BALL_SPEED;
+            }
+        }
+
+        // Don't leave any balls stranded on the paddle
+        if let Some(stuck) = stuck
+            && sticky_expired
+        {
+            commands
+                .entity(ball)
+                .remove::<StuckToPaddle>()
+                .insert(Velocity(stuck.velocity));
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Long headless runs played by the [`Autopilot`], checking the game stays sound.
+//!
+//! `cargo run -p breakout_clone --release -- --soak --ticks 100000` plays that many fixed ticks
+//! without a window and reports what happened, along with every tick a ball or the paddle ended
+//! up somewhere it can't be. The top paddle is played by its AI in versus. Every run reports its
+//! seed, and `--seed` plays it again.
+
+use std::fmt;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    BALL_DIAMETER, BOTTOM_WALL, Ball, BreakoutMode, BrickDestroyed, LEFT_WALL, Lives, Paddle,
+    RIGHT_WALL, TOP_WALL, TopPaddleControl, Velocity,
+    autopilot::Autopilot,
+    check_for_collisions,
+    headless::{TICK, enter_game, headless_app},
+    rng::GameRng,
+};
+
+// Problems reported in full, the rest are only counted
+const MAX_PROBLEMS: usize = 20;
+
+/// What to soak
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    pub ticks: u32,
+    pub autopilot: Autopilot,
+    pub mode: BreakoutMode,
+    /// Seeds the [`GameRng`], random unless given
+    pub seed: u64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        SoakConfig {
+            ticks: 10_000,
+            autopilot: Autopilot::HARD,
+            mode: BreakoutMode::Classic,
+            seed: rand::random(),
+        }
+    }
+}
+
+impl SoakConfig {
+    pub const USAGE: &str = "usage: breakout_clone --soak [--ticks N] [--autopilot easy|normal|hard] [--seed N] [--versus]";
+
+    /// The arguments after `--soak`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = SoakConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--versus" {
+                config.mode = BreakoutMode::Versus;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            let invalid = || format!("invalid value for {arg}: {value}");
+            match arg.as_str() {
+                "--ticks" => config.ticks = value.parse().map_err(|_| invalid())?,
+                "--seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "--autopilot" => {
+                    config.autopilot = Autopilot::from_name(&value).ok_or_else(invalid)?
+                }
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// What happened during a soak run
+#[derive(Debug, Clone, Default)]
+pub struct SoakReport {
+    /// The seed to play the run again with
+    pub seed: u64,
+    pub ticks: u32,
+    pub bricks_broken: usize,
+    /// Lives lost in the classic game
+    pub balls_lost: usize,
+    pub games_over: usize,
+    pub most_balls: usize,
+    /// Every tick something was wrong, the first [`MAX_PROBLEMS`] of them
+    pub problems: Vec<String>,
+    pub more_problems: usize,
+}
+
+impl SoakReport {
+    /// Whether the game stayed sound for the whole run
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    fn problem(&mut self, tick: u32, problem: String) {
+        if self.problems.len() < MAX_PROBLEMS {
+            self.problems.push(format!("tick {tick}: {problem}"));
+        } else {
+            self.more_problems += 1;
+        }
+    }
+}
+
+impl fmt::Display for SoakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed: {}", self.seed)?;
+        writeln!(f, "ticks: {}", self.ticks)?;
+        writeln!(f, "bricks broken: {}", self.bricks_broken)?;
+        writeln!(f, "balls lost: {}", self.balls_lost)?;
+        writeln!(f, "games over: {}", self.games_over)?;
+        writeln!(f, "most balls in play: {}", self.most_balls)?;
+        if self.passed() {
+            write!(f, "no problems")
+        } else {
+            write!(f, "problems:")?;
+            for problem in &self.problems {
+                write!(f, "\n  {problem}")?;
+            }
+            if self.more_problems > 0 {
+                write!(f, "\n  and {} more", self.more_problems)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Bricks broken since the last tick checked
+#[derive(Resource, Default)]
+struct BricksBroken(usize);
+
+/// Play `config.ticks` fixed ticks with the autopilot, one per update
+pub fn soak(config: &SoakConfig) -> SoakReport {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(config.autopilot)
+        .insert_resource(config.mode)
+        .insert_resource(TopPaddleControl::Ai)
+        .insert_resource(GameRng::seeded(config.seed))
+        .init_resource::<BricksBroken>()
+        .add_systems(FixedUpdate, count_bricks.after(check_for_collisions));
+    app.finish();
+    app.cleanup();
+    enter_game(&mut app);
+
+    let mut report = SoakReport {
+        seed: config.seed,
+        ticks: config.ticks,
+        ..default()
+    };
+    let mut lives = **app.world().resource::<Lives>();
+    for tick in 1..=config.ticks {
+        app.update();
+        let world = app.world_mut();
+
+        report.bricks_broken += std::mem::take(&mut world.resource_mut::<BricksBroken>().0);
+        // Running out of lives starts over with all of them in the same tick
+        let new_lives = **world.resource::<Lives>();
+        if new_lives > lives {
+            report.games_over += 1;
+            report.balls_lost += lives;
+        } else {
+            report.balls_lost += lives - new_lives;
+        }
+        lives = new_lives;
+
+        for problem in check(world) {
+            report.problem(tick, problem);
+        }
+        let balls = world.query_filtered::<(), With<Ball>>().iter(world).count();
+        report.most_balls = report.most_balls.max(balls);
+    }
+    report
+}
+
+fn count_bricks(mut brick_events: EventReader<BrickDestroyed>, mut broken: ResMut<BricksBroken>) {
+    broken.0 += brick_events.read().count();
+}
+
+/// What is wrong with the world, if anything
+fn check(world: &mut World) -> Vec<String> {
+    let mut problems = Vec::new();
+    // Balls are removed as soon as they touch the floor, or the ceiling in versus
+    let margin = BALL_DIAMETER;
+    let inside = |position: Vec2| {
+        position.is_finite()
+            && (LEFT_WALL - margin..=RIGHT_WALL + margin).contains(&position.x)
+            && (BOTTOM_WALL - margin..=TOP_WALL + margin).contains(&position.y)
+    };
+
+    let mut balls = 0;
+    for (ball, transform, velocity) in world
+        .query_filtered::<(Entity, &Transform, Option<&Velocity>), With<Ball>>()
+        .iter(world)
+    {
+        balls += 1;
+        let position = transform.translation.truncate();
+        if !inside(position) {
+            problems.push(format!("ball {ball} left the arena at {position}"));
+        }
+        if let Some(velocity) = velocity
+            && !velocity.is_finite()
+        {
+            problems.push(format!("ball {ball} has the velocity {}", **velocity));
+        }
+    }
+    if balls == 0 {
+        problems.push("no ball is in play".to_string());
+    }
+
+    for transform in world
+        .query_filtered::<&Transform, With<Paddle>>()
+        .iter(world)
+    {
+        let position = transform.translation.truncate();
+        if !inside(position) {
+            problems.push(format!("the paddle left the arena at {position}"));
+        }
+    }
+    problems
+}
@@ -0,0 +1,319 @@
+//! Two players facing each other, pong-style.
+//!
+//! In [`BreakoutMode::Versus`] a second paddle guards the top of the arena, played with its own
+//! keys and gamepad or by a simple AI, and the bricks sit in a band across the middle. Each brick
+//! scores for whoever touched the ball last, and a ball getting past a paddle scores
+//! [`GOAL_POINTS`] for the other side. The round ends once the bricks are cleared.
+//! Power-ups only drop in the classic game, where they can't favor one side.
+
+use action_map::ActionState;
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BALL_DIAMETER, BALL_SPEED, BOTTOM_WALL, Ball, Brick, BrickDestroyed, Collider,
+    GAP_BETWEEN_PADDLE_AND_FLOOR, INITIAL_BALL_DIRECTION, LEFT_WALL, LOADING, PADDLE_SIZE,
+    PADDLE_SPEED, RIGHT_WALL, STATE, TOP_WALL, Velocity, WALL_THICKNESS, actions::BreakoutAction,
+    balls::SpawnBallExt, check_for_collisions, clamp_paddle_x, move_paddle, spawn_bricks,
+};
+
+/// Points for getting the ball past the other paddle
+pub const GOAL_POINTS: usize = 5;
+const TOP_PADDLE_COLOR: Color = Color::srgb(0.7, 0.3, 0.3);
+// How far from the center a ball is served, on the side of the player receiving it
+const SERVE_DISTANCE: f32 = 150.0;
+// The AI paddle moves a little slower than a player can, so it can be beaten
+const AI_SPEED: f32 = 0.8;
+// The AI paddle slows down when the ball is closer than this to its center, instead of jittering
+const AI_SLOWDOWN_DISTANCE: f32 = 20.0;
+
+/// Plugin adding the versus mode, played when [`BreakoutMode::Versus`] is set
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BreakoutMode>()
+            .init_resource::<TopPaddleControl>()
+            .init_resource::<VersusScore>()
+            .register_type::<BreakoutMode>()
+            .register_type::<Side>()
+            .register_type::<TopPaddle>()
+            .register_type::<VersusScore>()
+            .add_systems(
+                OnEnter(STATE),
+                (reset_versus_score, spawn_top_paddle).run_if(is_versus),
+            )
+            .add_systems(
+                FixedUpdate,
+                (move_top_paddle, check_for_goals)
+                    .chain()
+                    .after(move_paddle)
+                    .before(check_for_collisions)
+                    .run_if(in_state(STATE).and(is_versus)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (score_bricks, finish_round)
+                    .chain()
+                    .after(check_for_collisions)
+                    .run_if(in_state(STATE).and(is_versus)),
+            )
+            .add_systems(
+                Update,
+                (toggle_versus, toggle_top_paddle_ai).run_if(in_state(STATE)),
+            );
+    }
+}
+
+/// Which game is played
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub enum BreakoutMode {
+    /// One paddle clearing the bricks, with lives and power-ups
+    #[default]
+    Classic,
+    /// Two paddles competing for the bricks in the middle
+    Versus,
+}
+
+/// Run condition for the systems of the versus mode
+pub fn is_versus(mode: Res<BreakoutMode>) -> bool {
+    *mode == BreakoutMode::Versus
+}
+
+/// The end of the arena a paddle guards
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub enum Side {
+    Bottom,
+    Top,
+}
+
+impl Side {
+    fn opponent(self) -> Side {
+        match self {
+            Side::Bottom => Side::Top,
+            Side::Top => Side::Bottom,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Side::Bottom => "Bottom",
+            Side::Top => "Top",
+        }
+    }
+
+    /// -1.0 for the bottom and 1.0 for the top, along the y axis
+    fn direction(self) -> f32 {
+        match self {
+            Side::Bottom => -1.0,
+            Side::Top => 1.0,
+        }
+    }
+}
+
+/// The second player's paddle, at the top of the arena
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct TopPaddle;
+
+/// The side which touched a ball last, which the bricks it breaks score for
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LastTouch(pub Side);
+
+/// Who moves the top paddle
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TopPaddleControl {
+    /// The second player, with A and D or the second gamepad
+    #[default]
+    Player,
+    /// An AI following the ball
+    Ai,
+}
+
+/// The points of each side in the current versus round
+#[derive(Resource, Debug, Default, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct VersusScore {
+    pub bottom: usize,
+    pub top: usize,
+}
+
+impl VersusScore {
+    fn side_mut(&mut self, side: Side) -> &mut usize {
+        match side {
+            Side::Bottom => &mut self.bottom,
+            Side::Top => &mut self.top,
+        }
+    }
+}
+
+/// Serve a new ball towards `side`, from its half of the arena
+pub fn serve(commands: &mut Commands, side: Side) {
+    let direction = Vec2::new(
+        INITIAL_BALL_DIRECTION.x,
+        side.direction() * INITIAL_BALL_DIRECTION.y.abs(),
+    );
+    commands.spawn_ball(
+        Vec3::new(0.0, side.direction() * SERVE_DISTANCE, 1.0),
+        direction.normalize() * BALL_SPEED,
+    );
+}
+
+fn reset_versus_score(mut score: ResMut<VersusScore>) {
+    *score = VersusScore::default();
+}
+
+fn spawn_top_paddle(mut commands: Commands) {
+    commands.spawn((
+        Sprite::from_color(TOP_PADDLE_COLOR, Vec2::ONE),
+        Transform {
+            translation: Vec3::new(0.0, TOP_WALL - GAP_BETWEEN_PADDLE_AND_FLOOR, 0.0),
+            scale: PADDLE_SIZE.extend(1.0),
+            ..default()
+        },
+        TopPaddle,
+        Side::Top,
+        Collider,
+        StateScoped(STATE),
+    ));
+}
+
+/// The restored top paddle of an imported scene
+pub fn top_paddle_visuals() -> (Sprite, Collider) {
+    (Sprite::from_color(TOP_PADDLE_COLOR, Vec2::ONE), Collider)
+}
+
+fn move_top_paddle(
+    actions: Res<ActionState<BreakoutAction>>,
+    control: Res<TopPaddleControl>,
+    mut paddle_transform: Single<&mut Transform, (With<TopPaddle>, Without<Ball>)>,
+    ball_query: Query<(&Transform, &Velocity), With<Ball>>,
+    time: Res<Time>,
+) {
+    let paddle_x = paddle_transform.translation.x;
+    let axis = match *control {
+        TopPaddleControl::Player => {
+            actions.value(BreakoutAction::TopPaddleRight)
+                - actions.value(BreakoutAction::TopPaddleLeft)
+        }
+        TopPaddleControl::Ai => {
+            // Follow the closest ball coming up, and wait in the middle otherwise
+            let target_x = ball_query
+                .iter()
+                .filter(|(_, velocity)| velocity.y > 0.0)
+                .max_by(|(a, _), (b, _)| a.translation.y.total_cmp(&b.translation.y))
+                .map_or(0.0, |(transform, _)| transform.translation.x);
+            (target_x - paddle_x) / AI_SLOWDOWN_DISTANCE * AI_SPEED
+        }
+    };
+
+    let new_paddle_x = paddle_x + axis.clamp(-1.0, 1.0) * PADDLE_SPEED * time.delta_secs();
+    paddle_transform.translation.x = clamp_paddle_x(new_paddle_x, paddle_transform.scale.x / 2.0);
+}
+
+/// Remove the balls which got past a paddle, scoring for the other side.
+///
+/// Like `check_for_lost_balls` in the classic game, this runs before `check_for_collisions`, so
+/// balls are never reflected off the bottom or top wall. A new ball is served once none are left.
+fn check_for_goals(
+    mut commands: Commands,
+    mut score: ResMut<VersusScore>,
+    ball_query: Query<(Entity, &Transform), With<Ball>>,
+) {
+    let goal = |side: Side| {
+        let y = match side {
+            Side::Bottom => BOTTOM_WALL,
+            Side::Top => TOP_WALL,
+        };
+        Aabb2d::new(
+            Vec2::new(0., y),
+            Vec2::new((RIGHT_WALL - LEFT_WALL) / 2., WALL_THICKNESS / 2.),
+        )
+    };
+
+    let mut balls_in_play = 0;
+    let mut conceded = None;
+    let mut balls_lost = 0;
+    for (ball, ball_transform) in &ball_query {
+        balls_in_play += 1;
+        let ball_circle =
+            BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.);
+        for side in [Side::Bottom, Side::Top] {
+            if ball_circle.intersects(&goal(side)) {
+                commands.entity(ball).despawn();
+                *score.side_mut(side.opponent()) += GOAL_POINTS;
+                info!("{} scores a goal!", side.opponent().name());
+                conceded = Some(side);
+                balls_lost += 1;
+                break;
+            }
+        }
+    }
+
+    if let Some(side) = conceded
+        && balls_lost == balls_in_play
+    {
+        serve(&mut commands, side);
+    }
+}
+
+fn score_bricks(mut brick_events: EventReader<BrickDestroyed>, mut score: ResMut<VersusScore>) {
+    for event in brick_events.read() {
+        if let Some(side) = event.owner {
+            *score.side_mut(side) += 1;
+        }
+    }
+}
+
+// Once the bricks are cleared, the side with the most points wins and a new round starts
+fn finish_round(
+    mut commands: Commands,
+    mut score: ResMut<VersusScore>,
+    brick_query: Query<(), With<Brick>>,
+) {
+    if !brick_query.is_empty() {
+        return;
+    }
+
+    match score.bottom.cmp(&score.top) {
+        std::cmp::Ordering::Greater => info!("Bottom wins {} to {}!", score.bottom, score.top),
+        std::cmp::Ordering::Less => info!("Top wins {} to {}!", score.top, score.bottom),
+        std::cmp::Ordering::Equal => info!("It's a draw at {} each!", score.bottom),
+    }
+    *score = VersusScore::default();
+    spawn_bricks(&mut commands, BreakoutMode::Versus);
+}
+
+/// Switch between the classic and versus games with `ToggleVersus` (F8 by default), starting over
+fn toggle_versus(
+    actions: Res<ActionState<BreakoutAction>>,
+    mut mode: ResMut<BreakoutMode>,
+    mut next_state: ResMut<NextState<common::AppState>>,
+) {
+    if actions.just_pressed(BreakoutAction::ToggleVersus) {
+        *mode = match *mode {
+            BreakoutMode::Classic => BreakoutMode::Versus,
+            BreakoutMode::Versus => BreakoutMode::Classic,
+        };
+        next_state.set(LOADING);
+    }
+}
+
+/// Hand the top paddle to the AI or back to the second player with `ToggleTopAi` (F9 by default)
+fn toggle_top_paddle_ai(
+    actions: Res<ActionState<BreakoutAction>>,
+    mut control: ResMut<TopPaddleControl>,
+) {
+    if actions.just_pressed(BreakoutAction::ToggleTopAi) {
+        *control = match *control {
+            TopPaddleControl::Player => TopPaddleControl::Ai,
+            TopPaddleControl::Ai => TopPaddleControl::Player,
+        };
+        info!("The top paddle is played by the {control:?}");
+    }
+}
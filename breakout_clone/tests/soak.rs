@@ -0,0 +1,30 @@
+//! Short soak runs of the autopilot, long ones are `cargo run -p breakout_clone -- --soak`
+
+use breakout_clone::{Autopilot, BreakoutMode, SoakConfig, soak};
+
+// About a minute of play
+const TICKS: u32 = 4000;
+
+#[test]
+fn the_autopilot_plays_the_classic_game() {
+    let report = soak(&SoakConfig {
+        ticks: TICKS,
+        autopilot: Autopilot::HARD,
+        mode: BreakoutMode::Classic,
+        seed: 1,
+    });
+    assert!(report.passed(), "{report}");
+    assert!(report.bricks_broken > 0, "no brick was broken\n{report}");
+}
+
+#[test]
+fn the_autopilot_plays_versus_against_the_ai() {
+    let report = soak(&SoakConfig {
+        ticks: TICKS,
+        autopilot: Autopilot::EASY,
+        mode: BreakoutMode::Versus,
+        seed: 2,
+    });
+    assert!(report.passed(), "{report}");
+    assert!(report.bricks_broken > 0, "no brick was broken\n{report}");
+}
@@ -0,0 +1,222 @@
+//! Loading each game's assets up front, in a loading state with a progress bar.
+//!
+//! A game lists its assets in an [`AssetCollection`] resource and registers it with
+//! [`AddAssetsExt::load_assets`]. Entering [`AppState::Loading`] starts loading them, along
+//! with the game's sounds, and the game starts once everything is loaded or has failed. Missing
+//! images and fonts are replaced by placeholders with a warning, and missing sounds stay
+//! silent, so a game still runs without its asset files.
+
+use std::{any::TypeId, collections::HashSet};
+
+use bevy::{
+    asset::{LoadState, RenderAssetUsages, UntypedAssetId},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{AppState, audio::SoundLibrary};
+
+const LOADING_FONT_SIZE: f32 = 32.0;
+const BAR_WIDTH: f32 = 400.0;
+const BAR_HEIGHT: f32 = 20.0;
+const BAR_BACKGROUND: Color = Color::srgb(0.2, 0.2, 0.2);
+const BAR_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+
+/// The assets folder shared by every game, given to the [`AssetPlugin`] of each binary.
+///
+/// Bevy resolves it from the crate's directory under cargo, and from the executable's directory
+/// otherwise, so it is the `assets` folder at the root of the workspace.
+pub const ASSET_FOLDER: &str = "../assets";
+
+/// Size of the placeholder image, and of its checker squares
+const PLACEHOLDER_SIZE: u32 = 64;
+const PLACEHOLDER_SQUARE: u32 = 16;
+
+/// The assets of a game, as a resource of typed handles
+pub trait AssetCollection: Resource {
+    /// Start loading every asset
+    fn load(asset_server: &AssetServer) -> Self;
+
+    /// Every handle of the collection, to follow their loading
+    fn handles(&self) -> Vec<UntypedHandle>;
+}
+
+/// Loading a game's assets before playing it
+pub trait AddAssetsExt {
+    /// Load `C` and the sounds named after `game` while in [`AppState::Loading`], then play
+    /// `game`. Every game has to do this, as the launcher starts games by loading them.
+    fn load_assets<C: AssetCollection>(&mut self, game: &'static str) -> &mut Self;
+}
+
+impl AddAssetsExt for App {
+    fn load_assets<C: AssetCollection>(&mut self, game: &'static str) -> &mut Self {
+        let loading = AppState::Loading(game);
+        self.add_systems(
+            OnEnter(loading.clone()),
+            (start_loading::<C>, spawn_loading_screen(game)),
+        )
+        .add_systems(Update, track_loading::<C>(game).run_if(in_state(loading)))
+    }
+}
+
+/// The filled part of the progress bar
+#[derive(Component)]
+struct LoadingBar;
+
+fn start_loading<C: AssetCollection>(world: &mut World) {
+    // Assets stay loaded once a game has been played
+    if !world.contains_resource::<C>() {
+        let collection = C::load(world.resource::<AssetServer>());
+        world.insert_resource(collection);
+    }
+}
+
+fn spawn_loading_screen(game: &'static str) -> impl FnMut(Commands) {
+    move |mut commands| {
+        let state = StateScoped(AppState::Loading(game));
+        commands.spawn((Camera2d, state.clone()));
+        commands.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            state,
+            children![
+                (
+                    Text::new(format!("Loading {game}")),
+                    TextFont::from_font_size(LOADING_FONT_SIZE),
+                ),
+                (
+                    Node {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(BAR_BACKGROUND),
+                    children![(
+                        LoadingBar,
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(BAR_COLOR),
+                    )],
+                ),
+            ],
+        ));
+    }
+}
+
+/// Fill the progress bar, replace the assets which failed, and play once everything is done
+fn track_loading<C: AssetCollection>(game: &'static str) -> impl FnMut(&mut World) {
+    move |world| {
+        let mut handles = world.resource::<C>().handles();
+        if let Some(library) = world.get_resource::<SoundLibrary>() {
+            handles.extend(library.handles(game).map(UntypedHandle::from));
+        }
+
+        let mut done = 0;
+        let mut failed = Vec::new();
+        let asset_server = world.resource::<AssetServer>();
+        for handle in &handles {
+            match asset_server.load_state(handle.id()) {
+                LoadState::Loaded => done += 1,
+                LoadState::Failed(_) => {
+                    done += 1;
+                    failed.push(handle.clone());
+                }
+                LoadState::NotLoaded | LoadState::Loading => {}
+            }
+        }
+
+        for handle in failed {
+            replace_missing(world, &handle);
+        }
+
+        let progress = if handles.is_empty() {
+            1.0
+        } else {
+            done as f32 / handles.len() as f32
+        };
+        let mut bars = world.query_filtered::<&mut Node, With<LoadingBar>>();
+        for mut bar in bars.iter_mut(world) {
+            bar.width = Val::Percent(progress * 100.0);
+        }
+
+        if done == handles.len() {
+            world
+                .resource_mut::<NextState<AppState>>()
+                .set(AppState::Playing(game));
+        }
+    }
+}
+
+/// Put a placeholder where a missing asset should be, unless one is already there
+fn replace_missing(world: &mut World, handle: &UntypedHandle) {
+    let path = handle
+        .path()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "an asset".to_string());
+
+    if handle.type_id() == TypeId::of::<Image>() {
+        let id = handle.id().typed::<Image>();
+        let mut images = world.resource_mut::<Assets<Image>>();
+        if !images.contains(id) {
+            warn!("Couldn't load {path}, using a placeholder image");
+            images.insert(id, placeholder_image());
+        }
+    } else if handle.type_id() == TypeId::of::<Font>() {
+        let id = handle.id().typed::<Font>();
+        let mut fonts = world.resource_mut::<Assets<Font>>();
+        if !fonts.contains(id)
+            && let Some(default_font) = fonts.get(&Handle::<Font>::default()).cloned()
+        {
+            warn!("Couldn't load {path}, using the default font");
+            fonts.insert(id, default_font);
+        }
+    } else if world
+        .get_resource_or_init::<WarnedMissing>()
+        .0
+        .insert(handle.id())
+    {
+        // Sounds which failed to load still go through the cooldowns and limits of the audio
+        // service, which only skips playing them
+        warn!("Couldn't load {path}, it will be left out");
+    }
+}
+
+/// Missing assets without a placeholder, which have already been warned about
+#[derive(Resource, Default)]
+struct WarnedMissing(HashSet<UntypedAssetId>);
+
+/// A magenta and black checkerboard, standing out as a missing texture
+fn placeholder_image() -> Image {
+    let mut data = Vec::with_capacity((PLACEHOLDER_SIZE * PLACEHOLDER_SIZE * 4) as usize);
+    for y in 0..PLACEHOLDER_SIZE {
+        for x in 0..PLACEHOLDER_SIZE {
+            let magenta = (x / PLACEHOLDER_SQUARE + y / PLACEHOLDER_SQUARE).is_multiple_of(2);
+            data.extend_from_slice(if magenta {
+                &[255, 0, 255, 255]
+            } else {
+                &[0, 0, 0, 255]
+            });
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: PLACEHOLDER_SIZE,
+            height: PLACEHOLDER_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
@@ -0,0 +1,475 @@
+//! Sound effects and music played by name through one audio service.
+//!
+//! Games register their sounds with [`AddSoundExt::add_sound`], which loads them up front, and
+//! play them with [`PlaySoundExt::play_sound`] from any system. The service limits how many
+//! copies of a sound play at once and how often it can restart, despawns sounds when they
+//! end, and scales every sound by the volume of its [`Bus`] from the saved [`AudioSettings`].
+//! Music loops while in a game's state with [`AddSoundExt::play_music_in`], and the volumes are
+//! changed in game with the [`VolumeAction`]s.
+
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use action_map::{Action, ActionMapPlugin, ActionState, Binding};
+use bevy::{
+    audio::{AudioSink, AudioSinkPlayback, PlaybackMode, Volume},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::game::AppState;
+
+/// Where the volume settings are saved
+pub const AUDIO_SETTINGS_PATH: &str = "config/audio.ron";
+
+/// Where the bindings of the [`VolumeAction`]s are saved
+pub const VOLUME_CONTROLS_PATH: &str = "config/volume-controls.ron";
+
+/// How much a [`VolumeAction`] turns its volume up or down
+const VOLUME_STEP: f32 = 0.1;
+
+/// How long the volumes stay on screen after being changed, in seconds
+const VOLUME_TEXT_DURATION: f32 = 2.0;
+
+/// Volume group a sound belongs to, on top of the master volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Sfx,
+    Music,
+}
+
+/// A sound which can be played by name, with one or more variants picked at random
+#[derive(Debug, Clone)]
+pub struct Sound {
+    paths: Vec<&'static str>,
+    bus: Bus,
+    volume: f32,
+    max_playing: usize,
+    cooldown: f32,
+    looping: bool,
+}
+
+impl Sound {
+    /// A one-shot sound effect. Several variants keep repeated effects from sounding mechanical.
+    pub fn sfx(paths: &[&'static str]) -> Self {
+        Self {
+            paths: paths.to_vec(),
+            bus: Bus::Sfx,
+            volume: 1.0,
+            max_playing: 4,
+            cooldown: 0.0,
+            looping: false,
+        }
+    }
+
+    /// A looping music track, playing once at most
+    pub fn music(path: &'static str) -> Self {
+        Self {
+            bus: Bus::Music,
+            max_playing: 1,
+            looping: true,
+            ..Self::sfx(&[path])
+        }
+    }
+
+    /// Volume of this sound, relative to its bus
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Ignore requests to play the sound while this many copies are already playing
+    pub fn max_playing(mut self, max_playing: usize) -> Self {
+        self.max_playing = max_playing;
+        self
+    }
+
+    /// Ignore requests to play the sound for `seconds` after it last started
+    pub fn cooldown(mut self, seconds: f32) -> Self {
+        self.cooldown = seconds;
+        self
+    }
+}
+
+/// The registered sounds, with their loaded variants
+#[derive(Resource, Default)]
+pub struct SoundLibrary {
+    sounds: HashMap<&'static str, LoadedSound>,
+}
+
+impl SoundLibrary {
+    /// The loaded variants of the sounds whose name starts with `game/`
+    pub fn handles(&self, game: &str) -> impl Iterator<Item = Handle<AudioSource>> + '_ {
+        let prefix = format!("{game}/");
+        self.sounds
+            .iter()
+            .filter(move |(name, _)| name.starts_with(&prefix))
+            .flat_map(|(_, loaded)| loaded.handles.iter().cloned())
+    }
+}
+
+struct LoadedSound {
+    sound: Sound,
+    handles: Vec<Handle<AudioSource>>,
+    /// Real time the sound last started, in seconds
+    last_played: Option<f32>,
+}
+
+/// Master and bus volumes, from 0 to 1, saved to [`AUDIO_SETTINGS_PATH`] when changed
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            sfx: 1.0,
+            music: 1.0,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Volume of `bus`, including the master volume
+    pub fn bus_volume(&self, bus: Bus) -> f32 {
+        let bus_volume = match bus {
+            Bus::Sfx => self.sfx,
+            Bus::Music => self.music,
+        };
+        (self.master * bus_volume).clamp(0.0, 1.0)
+    }
+
+    /// Load the settings saved at `path`, or the defaults if there are none
+    pub fn load_or_default(path: &Path) -> Self {
+        let Ok(saved) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&saved).unwrap_or_else(|error| {
+            warn!(
+                "Ignoring invalid audio settings in {}: {error}",
+                path.display()
+            );
+            Self::default()
+        })
+    }
+
+    /// Save the settings to `path`, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// Turning the volumes up and down while playing, rebound in their own settings menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum VolumeAction {
+    MasterDown,
+    MasterUp,
+    SfxDown,
+    SfxUp,
+    MusicDown,
+    MusicUp,
+}
+
+impl Action for VolumeAction {
+    const ALL: &'static [VolumeAction] = &[
+        VolumeAction::MasterDown,
+        VolumeAction::MasterUp,
+        VolumeAction::SfxDown,
+        VolumeAction::SfxUp,
+        VolumeAction::MusicDown,
+        VolumeAction::MusicUp,
+    ];
+
+    fn default_bindings(self) -> Vec<Binding> {
+        let key = match self {
+            VolumeAction::MasterDown => KeyCode::Minus,
+            VolumeAction::MasterUp => KeyCode::Equal,
+            VolumeAction::SfxDown => KeyCode::Comma,
+            VolumeAction::SfxUp => KeyCode::Period,
+            VolumeAction::MusicDown => KeyCode::Semicolon,
+            VolumeAction::MusicUp => KeyCode::Quote,
+        };
+        vec![Binding::Key(key)]
+    }
+}
+
+/// A request to the audio service, sent by [`PlaySoundExt`]
+#[derive(Event, Debug, Clone, Copy)]
+pub enum SoundCommand {
+    Play(&'static str),
+    Stop(&'static str),
+}
+
+/// A sound started by the audio service
+#[derive(Component, Debug)]
+pub struct PlayingSound {
+    pub name: &'static str,
+    bus: Bus,
+    volume: f32,
+}
+
+/// Shows the volumes for a moment after they are changed
+#[derive(Component)]
+struct VolumeText(Timer);
+
+/// Adds the audio service and its volume controls. [`AddSoundExt::add_sound`] adds it when
+/// needed.
+pub struct AudioManagerPlugin;
+
+impl Plugin for AudioManagerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(
+            ActionMapPlugin::<VolumeAction>::new(VOLUME_CONTROLS_PATH).with_menu_key(KeyCode::F5),
+        )
+        .init_resource::<SoundLibrary>()
+        .insert_resource(AudioSettings::load_or_default(Path::new(
+            AUDIO_SETTINGS_PATH,
+        )))
+        .add_event::<SoundCommand>()
+        .add_systems(PreStartup, load_sounds)
+        .add_systems(Update, (change_volume, show_volume).chain())
+        .add_systems(
+            PostUpdate,
+            (handle_sound_commands, apply_volume, save_audio_settings),
+        );
+    }
+}
+
+/// Registering sounds with the audio service
+pub trait AddSoundExt {
+    /// Register `sound` as `name`, loading it at startup. Prefix the name with the game's, as
+    /// the launcher shares one library between all the games.
+    fn add_sound(&mut self, name: &'static str, sound: Sound) -> &mut Self;
+
+    /// Play the sound registered as `name`, usually a [`Sound::music`] track, while in `state`
+    fn play_music_in(&mut self, name: &'static str, state: AppState) -> &mut Self;
+}
+
+impl AddSoundExt for App {
+    fn add_sound(&mut self, name: &'static str, sound: Sound) -> &mut Self {
+        if !self.is_plugin_added::<AudioManagerPlugin>() {
+            self.add_plugins(AudioManagerPlugin);
+        }
+        let previous = self
+            .world_mut()
+            .resource_mut::<SoundLibrary>()
+            .sounds
+            .insert(
+                name,
+                LoadedSound {
+                    sound,
+                    handles: Vec::new(),
+                    last_played: None,
+                },
+            );
+        if previous.is_some() {
+            warn!("The sound {name} was registered twice");
+        }
+        self
+    }
+
+    fn play_music_in(&mut self, name: &'static str, state: AppState) -> &mut Self {
+        self.add_systems(OnEnter(state.clone()), move |mut commands: Commands| {
+            commands.play_sound(name)
+        })
+        .add_systems(OnExit(state), move |mut commands: Commands| {
+            commands.stop_sound(name)
+        })
+    }
+}
+
+/// Playing registered sounds from any system with access to [`Commands`]
+pub trait PlaySoundExt {
+    /// Play the sound registered as `name`, unless too many copies are playing or it started
+    /// too recently
+    fn play_sound(&mut self, name: &'static str);
+
+    /// Stop every copy of the sound registered as `name`
+    fn stop_sound(&mut self, name: &'static str);
+}
+
+impl PlaySoundExt for Commands<'_, '_> {
+    fn play_sound(&mut self, name: &'static str) {
+        self.send_event(SoundCommand::Play(name));
+    }
+
+    fn stop_sound(&mut self, name: &'static str) {
+        self.send_event(SoundCommand::Stop(name));
+    }
+}
+
+fn load_sounds(mut library: ResMut<SoundLibrary>, asset_server: Res<AssetServer>) {
+    for loaded in library.sounds.values_mut() {
+        loaded.handles = loaded
+            .sound
+            .paths
+            .iter()
+            .map(|&path| asset_server.load(path))
+            .collect();
+    }
+}
+
+fn handle_sound_commands(
+    mut commands: Commands,
+    mut sound_commands: EventReader<SoundCommand>,
+    mut library: ResMut<SoundLibrary>,
+    settings: Res<AudioSettings>,
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    playing: Query<(Entity, &PlayingSound)>,
+) {
+    let now = time.elapsed_secs();
+    // Sounds started this frame aren't in the query yet
+    let mut started: HashMap<&'static str, usize> = HashMap::new();
+
+    for &sound_command in sound_commands.read() {
+        match sound_command {
+            SoundCommand::Play(name) => {
+                let Some(loaded) = library.sounds.get_mut(name) else {
+                    warn!("Can't play the unregistered sound {name}");
+                    continue;
+                };
+                if let Some(last_played) = loaded.last_played
+                    && now - last_played < loaded.sound.cooldown
+                {
+                    continue;
+                }
+                let already_playing = playing.iter().filter(|(_, sound)| sound.name == name);
+                let count = already_playing.count() + started.get(name).copied().unwrap_or(0);
+                if count >= loaded.sound.max_playing {
+                    continue;
+                }
+                loaded.last_played = Some(now);
+                *started.entry(name).or_default() += 1;
+
+                // Missing files stay silent, after the cooldown and limits so a game without its
+                // sounds still behaves the same
+                let available: Vec<&Handle<AudioSource>> = loaded
+                    .handles
+                    .iter()
+                    .filter(|handle| !asset_server.load_state(*handle).is_failed())
+                    .collect();
+                if available.is_empty() {
+                    continue;
+                }
+                let handle = available[rand::random_range(0..available.len())].clone();
+                let volume = loaded.sound.volume;
+                let bus = loaded.sound.bus;
+                let settings = PlaybackSettings {
+                    mode: if loaded.sound.looping {
+                        PlaybackMode::Loop
+                    } else {
+                        PlaybackMode::Despawn
+                    },
+                    volume: Volume::Linear(settings.bus_volume(bus) * volume),
+                    ..default()
+                };
+                commands.spawn((
+                    AudioPlayer::new(handle),
+                    settings,
+                    PlayingSound { name, bus, volume },
+                ));
+            }
+            SoundCommand::Stop(name) => {
+                for (entity, sound) in &playing {
+                    if sound.name == name {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Apply volume changes to the sounds already playing
+fn apply_volume(settings: Res<AudioSettings>, mut sinks: Query<(&PlayingSound, &mut AudioSink)>) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (sound, mut sink) in &mut sinks {
+        sink.set_volume(Volume::Linear(
+            settings.bus_volume(sound.bus) * sound.volume,
+        ));
+    }
+}
+
+fn change_volume(actions: Res<ActionState<VolumeAction>>, mut settings: ResMut<AudioSettings>) {
+    for &action in VolumeAction::ALL {
+        if !actions.just_pressed(action) {
+            continue;
+        }
+        let (volume, step) = match action {
+            VolumeAction::MasterDown => (&mut settings.master, -VOLUME_STEP),
+            VolumeAction::MasterUp => (&mut settings.master, VOLUME_STEP),
+            VolumeAction::SfxDown => (&mut settings.sfx, -VOLUME_STEP),
+            VolumeAction::SfxUp => (&mut settings.sfx, VOLUME_STEP),
+            VolumeAction::MusicDown => (&mut settings.music, -VOLUME_STEP),
+            VolumeAction::MusicUp => (&mut settings.music, VOLUME_STEP),
+        };
+        // Rounded to the step, so the volumes stay on the same ten levels
+        *volume = ((*volume + step) / VOLUME_STEP)
+            .round()
+            .clamp(0.0, 1.0 / VOLUME_STEP)
+            * VOLUME_STEP;
+    }
+}
+
+/// Show the volumes in a corner of the screen while they are being changed
+fn show_volume(
+    mut commands: Commands,
+    settings: Res<AudioSettings>,
+    time: Res<Time<Real>>,
+    mut text: Query<(Entity, &mut Text, &mut VolumeText)>,
+) {
+    if settings.is_changed() && !settings.is_added() {
+        let volumes = format!(
+            "Volume {:.0}%, sfx {:.0}%, music {:.0}%",
+            settings.master * 100.0,
+            settings.sfx * 100.0,
+            settings.music * 100.0
+        );
+        let timer = Timer::from_seconds(VOLUME_TEXT_DURATION, TimerMode::Once);
+        if let Ok((_, mut text, mut volume_text)) = text.single_mut() {
+            text.0 = volumes;
+            volume_text.0 = timer;
+        } else {
+            commands.spawn((
+                VolumeText(timer),
+                Text::new(volumes),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(5.0),
+                    right: Val::Px(5.0),
+                    ..default()
+                },
+                // Above the games' own UI
+                GlobalZIndex(10),
+            ));
+        }
+        return;
+    }
+
+    for (entity, _, mut volume_text) in &mut text {
+        if volume_text.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn save_audio_settings(settings: Res<AudioSettings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    if let Err(error) = settings.save(Path::new(AUDIO_SETTINGS_PATH)) {
+        warn!("Couldn't save the audio settings to {AUDIO_SETTINGS_PATH}: {error}");
+    }
+}
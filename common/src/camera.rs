@@ -0,0 +1,18 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::game::AppState;
+
+/// A system spawning a 2D camera looking at the middle of the primary window, which is
+/// despawned when leaving `state`.
+///
+/// This puts the world origin in the bottom left corner of the window, so world positions
+/// match window coordinates.
+pub fn spawn_camera(state: AppState) -> impl FnMut(Commands, Single<&Window, With<PrimaryWindow>>) {
+    move |mut commands, window| {
+        commands.spawn((
+            Camera2d,
+            Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
+            StateScoped(state.clone()),
+        ));
+    }
+}
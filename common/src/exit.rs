@@ -0,0 +1,28 @@
+use action_map::{Action, ActionState};
+use bevy::{app::AppExit, prelude::*};
+
+use crate::game::{AppState, InLauncher};
+
+/// A system leaving the game when `action` is pressed, usually bound to Escape.
+///
+/// This goes back to the menu when playing from the launcher, and quits otherwise.
+#[allow(clippy::type_complexity)]
+pub fn exit_on<A: Action>(
+    action: A,
+) -> impl FnMut(
+    Res<ActionState<A>>,
+    Option<Res<InLauncher>>,
+    ResMut<NextState<AppState>>,
+    EventWriter<AppExit>,
+) {
+    move |actions, in_launcher, mut next_state, mut app_exit_events| {
+        if !actions.just_pressed(action) {
+            return;
+        }
+        if in_launcher.is_some() {
+            next_state.set(AppState::Menu);
+        } else {
+            app_exit_events.write(AppExit::Success);
+        }
+    }
+}
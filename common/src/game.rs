@@ -0,0 +1,128 @@
+use action_map::{Action, ActionMapConfig};
+use bevy::prelude::*;
+
+/// Which game is running, or whether the launcher menu is shown.
+///
+/// Every game runs its systems only while in its own state, and marks its entities with
+/// [`StateScoped`] so they are despawned when going back to the menu.
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[states(scoped_entities)]
+pub enum AppState {
+    /// Choosing a game in the launcher
+    #[default]
+    Menu,
+    /// Loading the assets of the game with this name, before playing it
+    Loading(&'static str),
+    /// Playing the game with this name
+    Playing(&'static str),
+}
+
+/// A game which can be played from the launcher, or on its own.
+///
+/// The plugin should load its assets with [`AddAssetsExt::load_assets`], set the game up
+/// `OnEnter` its state, only run its systems `in_state` it, and despawn everything it spawned
+/// when leaving it, so it can be played again later.
+///
+/// [`AddAssetsExt::load_assets`]: crate::AddAssetsExt::load_assets
+pub trait GamePlugin: Plugin {
+    /// Unique name of the game, shown in the launcher
+    fn title(&self) -> &'static str;
+
+    /// One line about the game, shown in the launcher
+    fn description(&self) -> &'static str;
+
+    /// The state the game runs in
+    fn state(&self) -> AppState {
+        AppState::Playing(self.title())
+    }
+
+    /// The state starting the game, which loads its assets first
+    fn loading_state(&self) -> AppState {
+        AppState::Loading(self.title())
+    }
+
+    /// Where the game saves a round in progress. The launcher offers to continue it when the
+    /// file exists, starting the game with [`ContinueGame`].
+    fn save_path(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether the game can play itself as a demo, which the launcher shows when nobody is using
+    /// the menu. The game plays the demo while [`Attract`] holds its name.
+    fn attract_mode(&self) -> bool {
+        false
+    }
+}
+
+/// A game registered with [`AddGameExt::add_game`]
+#[derive(Debug, Clone)]
+pub struct GameInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub state: AppState,
+    pub loading_state: AppState,
+    pub save_path: Option<&'static str>,
+    pub attract_mode: bool,
+}
+
+/// Present when the game with this name should continue its saved round instead of starting a
+/// new one. The game removes it once the round is restored.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ContinueGame(pub &'static str);
+
+/// Present while the game with this name plays itself as a demo, started by the launcher. The
+/// launcher goes back to the menu on any input, and removes it once there.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Attract(pub &'static str);
+
+/// Every game added to the app, in the order they were added
+#[derive(Resource, Debug, Default)]
+pub struct Games(pub Vec<GameInfo>);
+
+/// Present when the games are played from the launcher, so exiting a game goes back to the
+/// menu instead of quitting
+#[derive(Resource, Debug, Default)]
+pub struct InLauncher;
+
+/// Adding games to an [`App`]
+pub trait AddGameExt {
+    /// Add `game` and list it in [`Games`]
+    fn add_game(&mut self, game: impl GamePlugin) -> &mut Self;
+
+    /// Only update the actions of `A`, and allow opening their settings menu, while in `state`.
+    ///
+    /// This keeps the settings menus of several games from opening at once in the launcher.
+    /// The `ActionMapPlugin` of `A` must have been added first.
+    fn scope_actions<A: Action>(&mut self, state: AppState) -> &mut Self;
+}
+
+impl AddGameExt for App {
+    fn add_game(&mut self, game: impl GamePlugin) -> &mut Self {
+        if !self.world().contains_resource::<State<AppState>>() {
+            self.init_state::<AppState>();
+        }
+        self.init_resource::<Games>();
+        self.world_mut().resource_mut::<Games>().0.push(GameInfo {
+            name: game.title(),
+            description: game.description(),
+            state: game.state(),
+            loading_state: game.loading_state(),
+            save_path: game.save_path(),
+            attract_mode: game.attract_mode(),
+        });
+        self.add_plugins(game)
+    }
+
+    fn scope_actions<A: Action>(&mut self, state: AppState) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<ActionMapConfig<A>>()
+            .enabled = false;
+        self.add_systems(
+            OnEnter(state.clone()),
+            |mut config: ResMut<ActionMapConfig<A>>| config.enabled = true,
+        )
+        .add_systems(OnExit(state), |mut config: ResMut<ActionMapConfig<A>>| {
+            config.enabled = false
+        })
+    }
+}
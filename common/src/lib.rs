@@ -0,0 +1,22 @@
+//! Pieces shared by every game in the workspace.
+//!
+//! - [`assets`]: loading a game's assets in a loading state, with placeholders for missing ones
+//! - [`camera`]: spawning the 2D camera
+//! - [`exit`]: quitting the game from a bound action
+//! - [`audio`]: playing sound effects and music by name, with volume settings
+//! - [`game`]: the [`GamePlugin`] trait and states letting the launcher run any of the games
+//! - [`scene`]: exporting a running game's world to a scene file, and importing it back
+
+pub mod assets;
+pub mod audio;
+pub mod camera;
+pub mod exit;
+pub mod game;
+pub mod scene;
+
+pub use assets::{AddAssetsExt, AssetCollection};
+pub use audio::{AddSoundExt, AudioSettings, PlaySoundExt, Sound};
+pub use camera::spawn_camera;
+pub use exit::exit_on;
+pub use game::{AddGameExt, AppState, Attract, ContinueGame, GamePlugin, Games, InLauncher};
+pub use scene::{AddSceneExt, SceneImported, SceneTypes};
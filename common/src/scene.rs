@@ -0,0 +1,230 @@
+//! Exporting the world of a running game to a `.scn.ron` scene file, and importing it back.
+//!
+//! A game lists the reflected types making up its world in [`SceneTypes`] and adds
+//! [`AddSceneExt::add_scene_export`], which writes them to `scenes/<game>.scn.ron` and reads
+//! them back on its actions. Only the entities carrying one of the listed entity markers are
+//! exported, with the listed components, so cameras and UI stay out of the scene. Visuals
+//! aren't saved either: the game adds them back when it receives [`SceneImported`].
+
+use std::{
+    any::TypeId,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use action_map::{Action, ActionState};
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    scene::{SceneFilter, serde::SceneDeserializer},
+};
+use serde::de::DeserializeSeed;
+
+use crate::game::AppState;
+
+/// Where the scenes are exported, next to where the game is run
+pub const SCENE_FOLDER: &str = "scenes";
+
+/// The file a game's scene is exported to
+pub fn scene_path(game: &str) -> PathBuf {
+    Path::new(SCENE_FOLDER).join(format!("{game}.scn.ron"))
+}
+
+/// The reflected types of a game which go in its scenes. Each type must be registered.
+#[derive(Debug, Clone)]
+pub struct SceneTypes {
+    /// Components marking the entities to export
+    entities: Vec<TypeId>,
+    components: SceneFilter,
+    resources: SceneFilter,
+}
+
+impl Default for SceneTypes {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            components: SceneFilter::deny_all(),
+            resources: SceneFilter::deny_all(),
+        }
+    }
+}
+
+impl SceneTypes {
+    /// Export the entities with the component `C`, along with it
+    pub fn entity<C: Component>(mut self) -> Self {
+        self.entities.push(TypeId::of::<C>());
+        self.component::<C>()
+    }
+
+    /// Export the component `C` of the exported entities
+    pub fn component<C: Component>(mut self) -> Self {
+        self.components = self.components.allow::<C>();
+        self
+    }
+
+    /// Export the resource `R`
+    pub fn resource<R: Resource>(mut self) -> Self {
+        self.resources = self.resources.allow::<R>();
+        self
+    }
+
+    /// The entities marked by one of the entity components
+    fn marked_entities(&self, world: &World) -> Vec<Entity> {
+        let components = world.components();
+        world
+            .iter_entities()
+            .filter(|entity| {
+                entity.archetype().components().any(|id| {
+                    components
+                        .get_info(id)
+                        .and_then(|info| info.type_id())
+                        .is_some_and(|type_id| self.entities.contains(&type_id))
+                })
+            })
+            .map(|entity| entity.id())
+            .collect()
+    }
+
+    /// A scene of the marked entities with the listed components, and the listed resources
+    pub fn extract(&self, world: &World) -> DynamicScene {
+        DynamicSceneBuilder::from_world(world)
+            .with_component_filter(self.components.clone())
+            .with_resource_filter(self.resources.clone())
+            .extract_entities(self.marked_entities(world).into_iter())
+            .extract_resources()
+            .build()
+    }
+
+    /// Despawn the marked entities and spawn those of `scene` instead, despawned when leaving
+    /// `state`, then apply its resources. Returns the spawned entities.
+    pub fn replace_with(
+        &self,
+        world: &mut World,
+        scene: &DynamicScene,
+        state: AppState,
+    ) -> Result<Vec<Entity>, Box<dyn Error>> {
+        for entity in self.marked_entities(world) {
+            world.despawn(entity);
+        }
+
+        let mut entity_map = EntityHashMap::default();
+        scene.write_to_world(world, &mut entity_map)?;
+        let entities: Vec<Entity> = entity_map.values().copied().collect();
+        for &entity in &entities {
+            world.entity_mut(entity).insert(StateScoped(state.clone()));
+        }
+        Ok(entities)
+    }
+}
+
+/// Write `scene` to `path` as RON, creating its folder if needed
+pub fn save_scene(world: &World, scene: &DynamicScene, path: &Path) -> Result<(), Box<dyn Error>> {
+    let contents = scene.serialize(&world.resource::<AppTypeRegistry>().read())?;
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Read the scene saved at `path`, whose types must be registered
+pub fn load_scene(world: &World, path: &Path) -> Result<DynamicScene, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut deserializer = ron::de::Deserializer::from_str(&contents)?;
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let scene = SceneDeserializer {
+        type_registry: &type_registry,
+    }
+    .deserialize(&mut deserializer)?;
+    Ok(scene)
+}
+
+/// Sent once the entities of a scene have been spawned, so the game can add what scenes leave
+/// out, such as sprites
+#[derive(Event, Debug, Clone)]
+pub struct SceneImported {
+    pub game: &'static str,
+    pub entities: Vec<Entity>,
+}
+
+/// Exporting and importing the world of a game
+pub trait AddSceneExt {
+    /// Export `types` of `game` to its [`scene_path`] when `export` is pressed, and replace them
+    /// with the exported ones when `import` is pressed, while playing it
+    fn add_scene_export<A: Action>(
+        &mut self,
+        game: &'static str,
+        types: SceneTypes,
+        export: A,
+        import: A,
+    ) -> &mut Self;
+}
+
+impl AddSceneExt for App {
+    fn add_scene_export<A: Action>(
+        &mut self,
+        game: &'static str,
+        types: SceneTypes,
+        export: A,
+        import: A,
+    ) -> &mut Self {
+        let state = AppState::Playing(game);
+        self.add_event::<SceneImported>().add_systems(
+            Update,
+            (
+                export_scene_on(game, types.clone(), export),
+                import_scene_on(game, types, import),
+            )
+                .run_if(in_state(state)),
+        )
+    }
+}
+
+fn export_scene_on<A: Action>(
+    game: &'static str,
+    types: SceneTypes,
+    action: A,
+) -> impl FnMut(&mut World) {
+    move |world| {
+        if !world.resource::<ActionState<A>>().just_pressed(action) {
+            return;
+        }
+        let path = scene_path(game);
+        let scene = types.extract(world);
+        match save_scene(world, &scene, &path) {
+            Ok(()) => info!(
+                "Exported {} entities to {}",
+                scene.entities.len(),
+                path.display()
+            ),
+            Err(error) => warn!("Couldn't export the scene to {}: {error}", path.display()),
+        }
+    }
+}
+
+fn import_scene_on<A: Action>(
+    game: &'static str,
+    types: SceneTypes,
+    action: A,
+) -> impl FnMut(&mut World) {
+    move |world| {
+        if !world.resource::<ActionState<A>>().just_pressed(action) {
+            return;
+        }
+        let path = scene_path(game);
+        let imported = load_scene(world, &path)
+            .and_then(|scene| types.replace_with(world, &scene, AppState::Playing(game)));
+        match imported {
+            Ok(entities) => {
+                info!(
+                    "Imported {} entities from {}",
+                    entities.len(),
+                    path.display()
+                );
+                world.send_event(SceneImported { game, entities });
+            }
+            Err(error) => warn!("Couldn't import the scene from {}: {error}", path.display()),
+        }
+    }
+}
@@ -1,62 +1,287 @@
-use bevy::prelude::*;
+//! Launcher running the games of the workspace in one app.
+//!
+//! A menu lists every game added with [`AddGameExt::add_game`]. Picking one enters its state,
+//! and exiting the game goes back to the menu instead of quitting. When nobody touches the menu
+//! for a while, the games with an attract mode take turns playing themselves until any input.
 
-pub struct HelloPlugin;
+use ball_game::BallGamePlugin;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use breakout_clone::BreakoutPlugin;
+use common::{
+    AddGameExt, AppState, Attract, ContinueGame, Games, InLauncher, assets::ASSET_FOLDER,
+};
+use std::path::Path;
 
-impl Plugin for HelloPlugin {
+const TITLE_FONT_SIZE: f32 = 48.0;
+const ENTRY_FONT_SIZE: f32 = 28.0;
+const HINT_FONT_SIZE: f32 = 18.0;
+
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.2);
+const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.35);
+const HINT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+/// Seconds without input on the menu before a demo starts
+const IDLE_SECONDS: f32 = 20.0;
+/// Seconds a demo plays before going back to the menu, so the next game gets its turn
+const DEMO_SECONDS: f32 = 60.0;
+
+/// Number keys picking the games, in the order they were added
+const GAME_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+pub struct LauncherPlugin;
+
+impl Plugin for LauncherPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(GreetTimer(Timer::from_seconds(2.0, TimerMode::Repeating)));
-        app.add_systems(Startup, add_people);
-        app.add_systems(Update, (hello_world, (update_people, greet_people).chain()));
+        app.insert_resource(InLauncher)
+            .add_game(BallGamePlugin)
+            .add_game(BreakoutPlugin)
+            .add_systems(OnEnter(AppState::Menu), (spawn_menu, end_demo))
+            .add_systems(
+                Update,
+                (
+                    click_game,
+                    press_game_key,
+                    quit_on_escape,
+                    start_demo_when_idle,
+                )
+                    .run_if(in_state(AppState::Menu)),
+            )
+            .add_systems(Update, stop_demo.run_if(resource_exists::<Demo>));
     }
 }
 
+/// Time since the last input on the menu
 #[derive(Resource)]
-struct GreetTimer(Timer);
-
-fn greet_people(time: Res<Time>, mut timer: ResMut<GreetTimer>, query: Query<&Name, With<Person>>) {
-    // update our timer with the time elapsed since the last update
-    // if that caused the timer to finish, we say hello to everyone
-    if timer.0.tick(time.delta()).just_finished() {
-        for name in &query {
-            println!("hello {}!", name.0);
-        }
+struct Idle(Timer);
+
+/// A game playing itself, see [`Attract`], until the timer finishes
+#[derive(Resource)]
+struct Demo(Timer);
+
+/// Whether anything was pressed this frame
+#[derive(SystemParam)]
+struct AnyInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl AnyInput<'_, '_> {
+    fn just_pressed(&self) -> bool {
+        self.keys.get_just_pressed().next().is_some()
+            || self.mouse_buttons.get_just_pressed().next().is_some()
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_just_pressed().next().is_some())
     }
 }
 
+/// Button starting the game by entering this state, continuing its saved round if it has the
+/// game's name
+#[derive(Component)]
+struct GameButton {
+    state: AppState,
+    continue_game: Option<&'static str>,
+}
+
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugins(HelloPlugin)
+        // Every game loads its files from the workspace's shared assets folder, the ones
+        // breakout misses get placeholders
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            file_path: ASSET_FOLDER.to_string(),
+            ..default()
+        }))
+        .add_plugins(LauncherPlugin)
         .run();
 }
 
-/**
-This is where you put your function description
+fn spawn_menu(mut commands: Commands, games: Res<Games>) {
+    commands.insert_resource(ClearColor::default());
+    commands.spawn((Camera2d, StateScoped(AppState::Menu)));
 
-- supports markdown too
- */
-fn hello_world() {
-    //blah blah blah
-    println!("Hello, world!");
-}
+    let root = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            StateScoped(AppState::Menu),
+        ))
+        .id();
 
-#[derive(Component)]
-struct Person;
+    commands.spawn((
+        Text::new("Pick a game"),
+        TextFont::from_font_size(TITLE_FONT_SIZE),
+        Node {
+            margin: UiRect::bottom(Val::Px(24.0)),
+            ..default()
+        },
+        ChildOf(root),
+    ));
 
-#[derive(Component)]
-struct Name(String);
+    for (index, game) in games.0.iter().enumerate() {
+        commands
+            .spawn((
+                Button,
+                GameButton {
+                    state: game.loading_state.clone(),
+                    continue_game: None,
+                },
+                Node {
+                    width: Val::Px(600.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                ChildOf(root),
+            ))
+            .with_child((
+                Text::new(format!(
+                    "{}. {} - {}",
+                    index + 1,
+                    game.name,
+                    game.description
+                )),
+                TextFont::from_font_size(ENTRY_FONT_SIZE),
+            ));
+
+        if game.save_path.is_some_and(|path| Path::new(path).exists()) {
+            commands
+                .spawn((
+                    Button,
+                    GameButton {
+                        state: game.loading_state.clone(),
+                        continue_game: Some(game.name),
+                    },
+                    Node {
+                        width: Val::Px(600.0),
+                        padding: UiRect::all(Val::Px(12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                    ChildOf(root),
+                ))
+                .with_child((
+                    Text::new(format!("   Continue {}", game.name)),
+                    TextFont::from_font_size(ENTRY_FONT_SIZE),
+                ));
+        }
+    }
+
+    commands.spawn((
+        Text::new("click or press its number to play, Escape goes back to this menu or quits"),
+        TextFont::from_font_size(HINT_FONT_SIZE),
+        TextColor(HINT_COLOR),
+        Node {
+            margin: UiRect::top(Val::Px(24.0)),
+            ..default()
+        },
+        ChildOf(root),
+    ));
+}
 
-fn add_people(mut commands: Commands) {
-    commands.spawn((Person, Name("Elaina Proctor".to_string())));
-    commands.spawn((Person, Name("Renzo Hume".to_string())));
-    commands.spawn((Person, Name("Zayna Nieves".to_string())));
+fn click_game(
+    mut commands: Commands,
+    mut buttons: Query<(&Interaction, &GameButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button, mut color) in &mut buttons {
+        match interaction {
+            Interaction::Pressed => {
+                if let Some(name) = button.continue_game {
+                    commands.insert_resource(ContinueGame(name));
+                }
+                next_state.set(button.state.clone());
+            }
+            Interaction::Hovered => color.0 = HOVERED_BUTTON_COLOR,
+            Interaction::None => color.0 = BUTTON_COLOR,
+        }
+    }
 }
 
-fn update_people(mut query: Query<&mut Name, With<Person>>) {
-    for mut name in &mut query {
-        if name.0 == "Elaina Proctor" {
-            name.0 = "Elaina Hume".to_string();
-            break; // We don't need to change any other names.
+fn press_game_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    games: Res<Games>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (key, game) in GAME_KEYS.iter().zip(&games.0) {
+        if keys.just_pressed(*key) {
+            next_state.set(game.loading_state.clone());
         }
     }
 }
+
+/// Start the demo of the next game with an attract mode, once the menu has been left alone
+#[allow(clippy::too_many_arguments)]
+fn start_demo_when_idle(
+    mut commands: Commands,
+    input: AnyInput,
+    mut cursor_events: EventReader<CursorMoved>,
+    games: Res<Games>,
+    mut idle: ResMut<Idle>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut demos_played: Local<usize>,
+) {
+    let moved = cursor_events.read().count() > 0;
+    if input.just_pressed() || moved {
+        idle.0.reset();
+        return;
+    }
+    if !idle.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let attract: Vec<_> = games.0.iter().filter(|game| game.attract_mode).collect();
+    if attract.is_empty() {
+        return;
+    }
+    let game = attract[*demos_played % attract.len()];
+    *demos_played += 1;
+    info!("Nobody is playing, showing a demo of {}", game.name);
+    commands.insert_resource(Attract(game.name));
+    commands.insert_resource(Demo(Timer::from_seconds(DEMO_SECONDS, TimerMode::Once)));
+    next_state.set(game.loading_state.clone());
+}
+
+/// Go back to the menu on any input, or once the demo has played long enough
+fn stop_demo(
+    input: AnyInput,
+    mut demo: ResMut<Demo>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if input.just_pressed() || demo.0.tick(time.delta()).just_finished() {
+        next_state.set(AppState::Menu);
+    }
+}
+
+/// Back on the menu, after the game left the demo, with the idle time counted from now
+fn end_demo(mut commands: Commands) {
+    commands.remove_resource::<Attract>();
+    commands.remove_resource::<Demo>();
+    commands.insert_resource(Idle(Timer::from_seconds(IDLE_SECONDS, TimerMode::Once)));
+}
+
+fn quit_on_escape(keys: Res<ButtonInput<KeyCode>>, mut app_exit_events: EventWriter<AppExit>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        app_exit_events.write(AppExit::Success);
+    }
+}
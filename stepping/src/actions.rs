@@ -0,0 +1,62 @@
+//! The stepping controls, which can be rebound from their own settings menu.
+
+use action_map::{Action, Binding};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where rebound stepping controls are saved, unless set with [`crate::SteppingPlugin::with_controls`]
+pub const STEPPING_CONTROLS_PATH: &str = "config/stepping_controls.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SteppingAction {
+    ToggleStepping,
+    /// Run the next system while stepping
+    StepSystem,
+    /// Run the rest of the frame while stepping
+    StepFrame,
+    PrintStepping,
+    SelectPreviousSystem,
+    SelectNextSystem,
+    /// Toggle the breakpoint of the selected system while stepping
+    ToggleBreakpoint,
+    /// Keep running frames until a breakpoint or an enabled break condition is hit
+    RunToBreakpoint,
+    /// Keep running frames until any break condition is hit, even a disabled one
+    RunUntilCondition,
+    /// Restore the world as it was one `FixedUpdate` tick earlier while stepping, scrubbing when held
+    StepBackward,
+    /// Move forward again through the recorded ticks while stepping, scrubbing when held
+    StepForward,
+}
+
+impl Action for SteppingAction {
+    const ALL: &'static [SteppingAction] = &[
+        SteppingAction::ToggleStepping,
+        SteppingAction::StepSystem,
+        SteppingAction::StepFrame,
+        SteppingAction::PrintStepping,
+        SteppingAction::SelectPreviousSystem,
+        SteppingAction::SelectNextSystem,
+        SteppingAction::ToggleBreakpoint,
+        SteppingAction::RunToBreakpoint,
+        SteppingAction::RunUntilCondition,
+        SteppingAction::StepBackward,
+        SteppingAction::StepForward,
+    ];
+
+    fn default_bindings(self) -> Vec<Binding> {
+        match self {
+            SteppingAction::ToggleStepping => vec![Binding::Key(KeyCode::Backquote)],
+            SteppingAction::StepSystem => vec![Binding::Key(KeyCode::KeyN)],
+            SteppingAction::StepFrame => vec![Binding::Key(KeyCode::Space)],
+            SteppingAction::PrintStepping => vec![Binding::Key(KeyCode::Slash)],
+            SteppingAction::SelectPreviousSystem => vec![Binding::Key(KeyCode::PageUp)],
+            SteppingAction::SelectNextSystem => vec![Binding::Key(KeyCode::PageDown)],
+            SteppingAction::ToggleBreakpoint => vec![Binding::Key(KeyCode::Enter)],
+            SteppingAction::RunToBreakpoint => vec![Binding::Key(KeyCode::KeyR)],
+            SteppingAction::RunUntilCondition => vec![Binding::Key(KeyCode::KeyC)],
+            SteppingAction::StepBackward => vec![Binding::Key(KeyCode::BracketLeft)],
+            SteppingAction::StepForward => vec![Binding::Key(KeyCode::BracketRight)],
+        }
+    }
+}
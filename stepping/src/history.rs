@@ -0,0 +1,453 @@
+//! A ring buffer of snapshots of the gameplay state, taken after every `FixedUpdate` tick,
+//! so stepping can go backward and scrub through the recent past.
+
+use std::{any::Any, collections::HashMap, collections::VecDeque, sync::Arc};
+
+use action_map::ActionState;
+use bevy::{
+    ecs::{component::Mutable, query::QueryFilter, schedule::Stepping},
+    prelude::*,
+};
+
+use crate::{FONT_COLOR, State, actions::SteppingAction, inspector::Inspector};
+
+type Saved = Box<dyn Any + Send + Sync>;
+type SaveFn = Arc<dyn Fn(&mut World) -> Saved + Send + Sync>;
+type RestoreFn = Arc<dyn Fn(&mut World, &Saved, &mut Respawned) + Send + Sync>;
+type UnchangedFn = fn(&Saved, &Saved, &Respawned) -> bool;
+
+/// One part of the world recorded in every snapshot
+#[derive(Clone)]
+pub(crate) struct Track {
+    save: SaveFn,
+    restore: RestoreFn,
+    unchanged: UnchangedFn,
+    // restored before the other tracks, so they find the entities it respawns
+    respawns: bool,
+}
+
+/// The entities respawned by the presence tracks, by the id they had when they were recorded
+#[derive(Default)]
+pub(crate) struct Respawned(HashMap<Entity, Entity>);
+
+impl Respawned {
+    /// The entity now standing for `entity`, after it was respawned any number of times
+    fn resolve(&self, mut entity: Entity) -> Entity {
+        while let Some(&respawned) = self.0.get(&entity) {
+            entity = respawned;
+        }
+        entity
+    }
+}
+
+fn unchanged<T: PartialEq + 'static>(a: &Saved, b: &Saved, _: &Respawned) -> bool {
+    a.downcast_ref::<T>() == b.downcast_ref::<T>()
+}
+
+/// Whether the same entities have the same values, in any order, an entity respawned since
+/// `old` was recorded counting as the one it stands for
+fn unchanged_entities<T: PartialEq + 'static>(
+    old: &Saved,
+    new: &Saved,
+    respawned: &Respawned,
+) -> bool {
+    let (Some(old), Some(new)) = (
+        old.downcast_ref::<Vec<(Entity, T)>>(),
+        new.downcast_ref::<Vec<(Entity, T)>>(),
+    ) else {
+        return false;
+    };
+    old.len() == new.len()
+        && old.iter().all(|(entity, value)| {
+            let entity = respawned.resolve(*entity);
+            new.iter()
+                .any(|(new_entity, new_value)| *new_entity == entity && new_value == value)
+        })
+}
+
+/// Record the `C` component of every entity matching `F`.
+///
+/// Only entities which still exist, or were respawned by a presence track, get their component
+/// restored.
+pub(crate) fn component_track<C, F>() -> Track
+where
+    C: Component<Mutability = Mutable> + Clone + PartialEq,
+    F: QueryFilter + 'static,
+{
+    Track {
+        save: Arc::new(|world| {
+            let mut query = world.query_filtered::<(Entity, &C), F>();
+            let saved: Vec<(Entity, C)> = query
+                .iter(world)
+                .map(|(entity, value)| (entity, value.clone()))
+                .collect();
+            Box::new(saved)
+        }),
+        restore: Arc::new(|world, saved, respawned| {
+            let Some(saved) = saved.downcast_ref::<Vec<(Entity, C)>>() else {
+                return;
+            };
+            for (entity, value) in saved {
+                if let Some(mut component) = world.get_mut::<C>(respawned.resolve(*entity)) {
+                    *component = value.clone();
+                }
+            }
+        }),
+        unchanged: unchanged_entities::<C>,
+        respawns: false,
+    }
+}
+
+/// Record the value of the `R` resource
+pub(crate) fn resource_track<R: Resource + Clone + PartialEq>() -> Track {
+    Track {
+        save: Arc::new(|world| Box::new(world.get_resource::<R>().cloned())),
+        restore: Arc::new(|world, saved, _| {
+            if let Some(Some(value)) = saved.downcast_ref::<Option<R>>() {
+                world.insert_resource(value.clone());
+            }
+        }),
+        unchanged: unchanged::<Option<R>>,
+        respawns: false,
+    }
+}
+
+/// Record which entities match `F`, despawning the ones which didn't exist yet when restoring
+/// and calling `respawn` with the [`Transform`] of the ones which have been despawned since.
+///
+/// The entity `respawn` returns stands for the despawned one from then on, so the component
+/// tracks restore their values on it, in this snapshot and in every other one.
+pub(crate) fn presence_track<F: QueryFilter + 'static>(
+    respawn: fn(&mut Commands, &Transform) -> Entity,
+) -> Track {
+    Track {
+        save: Arc::new(|world| {
+            let mut query = world.query_filtered::<(Entity, &Transform), F>();
+            let saved: Vec<(Entity, Transform)> = query
+                .iter(world)
+                .map(|(entity, transform)| (entity, *transform))
+                .collect();
+            Box::new(saved)
+        }),
+        restore: Arc::new(move |world, saved, respawned| {
+            let Some(saved) = saved.downcast_ref::<Vec<(Entity, Transform)>>() else {
+                return;
+            };
+            let wanted: Vec<Entity> = saved
+                .iter()
+                .map(|(entity, _)| respawned.resolve(*entity))
+                .collect();
+            let mut query = world.query_filtered::<Entity, F>();
+            let current: Vec<Entity> = query.iter(world).collect();
+            for entity in &current {
+                if !wanted.contains(entity) {
+                    world.despawn(*entity);
+                }
+            }
+
+            let mut commands = world.commands();
+            for ((_, transform), entity) in saved.iter().zip(wanted) {
+                if !current.contains(&entity) {
+                    respawned
+                        .0
+                        .insert(entity, respawn(&mut commands, transform));
+                }
+            }
+            world.flush();
+        }),
+        unchanged: unchanged_entities::<Transform>,
+        respawns: true,
+    }
+}
+
+struct Snapshot {
+    // counts the snapshots taken since the start, ticks which changed nothing don't take one
+    number: u64,
+    saved: Vec<Saved>,
+}
+
+/// How long a rewind key must be held before it starts scrubbing, in seconds
+const SCRUB_DELAY: f32 = 0.3;
+
+#[derive(Resource)]
+pub(crate) struct History {
+    tracks: Vec<Track>,
+    capacity: usize,
+    snapshots: VecDeque<Snapshot>,
+    // number of snapshots taken since the start
+    taken: u64,
+    // index in `snapshots` of the restored snapshot, while looking at the past
+    position: Option<usize>,
+    // how long the rewind keys have been held
+    held_for: f32,
+    respawned: Respawned,
+}
+
+impl History {
+    pub(crate) fn new(mut tracks: Vec<Track>, capacity: usize) -> History {
+        tracks.sort_by_key(|track| !track.respawns);
+        History {
+            tracks,
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+            taken: 0,
+            position: None,
+            held_for: 0.0,
+            respawned: Respawned::default(),
+        }
+    }
+
+    /// Put the world back the way it was in the snapshot at `index`
+    fn restore(&mut self, world: &mut World, index: usize) {
+        let snapshot = &self.snapshots[index];
+        for (track, saved) in self.tracks.iter().zip(&snapshot.saved) {
+            (track.restore)(world, saved, &mut self.respawned);
+        }
+        self.position = Some(index);
+    }
+}
+
+/// Snapshot the tracked state at the end of a `FixedUpdate` tick.
+///
+/// Ticks which changed nothing, like the ones passing while stepping is paused, aren't recorded.
+/// When the world was rewound, the snapshots after the restored one are dropped as soon as
+/// the simulation moves on, as it is now taking a different path.
+pub(crate) fn record_snapshot(world: &mut World) {
+    world.resource_scope(|world, mut history: Mut<History>| {
+        if history.tracks.is_empty() {
+            return;
+        }
+
+        let saved: Vec<Saved> = history
+            .tracks
+            .iter()
+            .map(|track| (track.save)(world))
+            .collect();
+        let latest = match history.position {
+            Some(position) => history.snapshots.get(position),
+            None => history.snapshots.back(),
+        };
+        if let Some(latest) = latest
+            && history
+                .tracks
+                .iter()
+                .zip(latest.saved.iter().zip(&saved))
+                .all(|(track, (old, new))| (track.unchanged)(old, new, &history.respawned))
+        {
+            return;
+        }
+
+        if let Some(position) = history.position.take() {
+            history.snapshots.truncate(position + 1);
+        }
+        if history.snapshots.len() == history.capacity {
+            history.snapshots.pop_front();
+        }
+        history.taken += 1;
+        let number = history.taken;
+        history.snapshots.push_back(Snapshot { number, saved });
+    });
+}
+
+/// Go backward and forward through the recorded snapshots while stepping.
+///
+/// Tapping a key moves a single tick, holding it scrubs through one tick per frame.
+pub(crate) fn handle_rewind(world: &mut World) {
+    let (backward, forward) = {
+        let actions = world.resource::<ActionState<SteppingAction>>();
+        (
+            actions.pressed(SteppingAction::StepBackward),
+            actions.pressed(SteppingAction::StepForward),
+        )
+    };
+    let delta = world.resource::<Time<Real>>().delta_secs();
+    let enabled = world.resource::<Stepping>().is_enabled();
+
+    let restored = world.resource_scope(|world, mut history: Mut<History>| {
+        let history = &mut *history;
+        if !enabled || backward == forward || history.snapshots.is_empty() {
+            history.held_for = 0.0;
+            return false;
+        }
+
+        let first_press = history.held_for == 0.0;
+        history.held_for += delta;
+        if !first_press && history.held_for < SCRUB_DELAY {
+            return false;
+        }
+
+        let latest = history.snapshots.len() - 1;
+        let current = history.position.unwrap_or(latest);
+        let target = if backward {
+            current.saturating_sub(1)
+        } else {
+            (current + 1).min(latest)
+        };
+        if history.position.is_some() && target == current {
+            return false;
+        }
+
+        history.restore(world, target);
+        true
+    });
+
+    if restored {
+        // going back in time interrupts running to a breakpoint
+        world.resource_mut::<State>().run = None;
+        world.resource_mut::<Inspector>().mark_stepped();
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct HistoryText;
+
+pub(crate) fn build_history_text(commands: &mut Commands, ui: Entity) {
+    commands.spawn((
+        HistoryText,
+        Text::default(),
+        TextFont::default(),
+        TextColor(FONT_COLOR),
+        ChildOf(ui),
+    ));
+}
+
+pub(crate) fn update_history_text(
+    history: Res<History>,
+    mut text: Single<&mut Text, With<HistoryText>>,
+) {
+    let (Some(first), Some(latest)) = (history.snapshots.front(), history.snapshots.back()) else {
+        text.0 = "History: nothing recorded yet".to_string();
+        return;
+    };
+    let shown = history
+        .position
+        .and_then(|position| history.snapshots.get(position))
+        .unwrap_or(latest);
+    let behind = latest.number - shown.number;
+    text.0 = format!(
+        "History: snapshot {} of {}..={}{}",
+        shown.number,
+        first.number,
+        latest.number,
+        if behind > 0 {
+            format!(" ({behind} back)")
+        } else {
+            String::new()
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Clone, PartialEq, Debug)]
+    struct Counter(u32);
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[derive(Component, Clone, PartialEq, Debug)]
+    struct Value(u32);
+
+    fn world(tracks: Vec<Track>, capacity: usize) -> World {
+        let mut world = World::new();
+        world.insert_resource(Counter(0));
+        world.insert_resource(History::new(tracks, capacity));
+        world
+    }
+
+    fn tick(world: &mut World, counter: u32) {
+        world.resource_mut::<Counter>().0 = counter;
+        record_snapshot(world);
+    }
+
+    fn numbers(world: &World) -> Vec<u64> {
+        let history = world.resource::<History>();
+        history
+            .snapshots
+            .iter()
+            .map(|snapshot| snapshot.number)
+            .collect()
+    }
+
+    fn restore(world: &mut World, index: usize) {
+        world.resource_scope(|world, mut history: Mut<History>| history.restore(world, index));
+    }
+
+    fn respawn(commands: &mut Commands, transform: &Transform) -> Entity {
+        commands.spawn((Marker, *transform, Value(0))).id()
+    }
+
+    #[test]
+    fn ticks_which_changed_nothing_are_skipped() {
+        let mut world = world(vec![resource_track::<Counter>()], 10);
+        tick(&mut world, 1);
+        tick(&mut world, 1);
+        tick(&mut world, 2);
+        tick(&mut world, 2);
+        assert_eq!(numbers(&world), [1, 2]);
+    }
+
+    #[test]
+    fn the_oldest_snapshots_are_dropped_beyond_the_capacity() {
+        let mut world = world(vec![resource_track::<Counter>()], 3);
+        for counter in 1..=5 {
+            tick(&mut world, counter);
+        }
+        assert_eq!(numbers(&world), [3, 4, 5]);
+    }
+
+    #[test]
+    fn moving_on_after_a_rewind_drops_the_later_snapshots() {
+        let mut world = world(vec![resource_track::<Counter>()], 10);
+        for counter in 1..=3 {
+            tick(&mut world, counter);
+        }
+
+        restore(&mut world, 0);
+        assert_eq!(world.resource::<Counter>(), &Counter(1));
+        // Paused, nothing changes and the future is kept to scrub forward again
+        record_snapshot(&mut world);
+        assert_eq!(numbers(&world), [1, 2, 3]);
+
+        tick(&mut world, 10);
+        assert_eq!(numbers(&world), [1, 4]);
+        assert_eq!(world.resource::<History>().position, None);
+    }
+
+    #[test]
+    fn respawned_entities_get_their_components_back() {
+        let tracks = vec![
+            component_track::<Value, With<Marker>>(),
+            presence_track::<With<Marker>>(respawn),
+        ];
+        let mut world = world(tracks, 10);
+        let entity = world
+            .spawn((Marker, Transform::from_xyz(1.0, 2.0, 0.0), Value(7)))
+            .id();
+        record_snapshot(&mut world);
+        world.despawn(entity);
+        record_snapshot(&mut world);
+        assert_eq!(numbers(&world), [1, 2]);
+
+        restore(&mut world, 0);
+        let mut query = world.query_filtered::<(Entity, &Transform, &Value), With<Marker>>();
+        let restored: Vec<(Entity, Vec3, Value)> = query
+            .iter(&world)
+            .map(|(entity, transform, value)| (entity, transform.translation, value.clone()))
+            .collect();
+        assert_eq!(restored.len(), 1);
+        let (respawned, translation, value) = restored[0].clone();
+        assert_ne!(respawned, entity);
+        assert_eq!((translation, value), (Vec3::new(1.0, 2.0, 0.0), Value(7)));
+
+        // The respawned entity stands for the recorded one, so nothing changed
+        record_snapshot(&mut world);
+        assert_eq!(numbers(&world), [1, 2]);
+
+        // And going forward again despawns it
+        restore(&mut world, 1);
+        assert_eq!(query.iter(&world).count(), 0);
+    }
+}
@@ -0,0 +1,227 @@
+//! A panel listing the watched components and resources while stepping,
+//! along with what changed during the last step.
+
+use std::fmt::Debug;
+
+use bevy::{
+    ecs::{query::QueryFilter, schedule::Stepping},
+    prelude::*,
+};
+
+use crate::FONT_COLOR;
+
+/// Produces the rows of one watch, as (name, value) pairs
+pub(crate) type WatchFn = fn(&mut World) -> Vec<(String, String)>;
+
+pub(crate) fn component_rows<C: Component + Debug, F: QueryFilter + 'static>(
+    world: &mut World,
+) -> Vec<(String, String)> {
+    let component = short_type_name(std::any::type_name::<C>());
+    let mut query = world.query_filtered::<(Entity, &C), F>();
+    query
+        .iter(world)
+        .map(|(entity, value)| (format!("{entity} {component}"), format!("{value:.2?}")))
+        .collect()
+}
+
+pub(crate) fn resource_rows<R: Resource + Debug>(world: &mut World) -> Vec<(String, String)> {
+    let name = short_type_name(std::any::type_name::<R>());
+    let value = match world.get_resource::<R>() {
+        Some(value) => format!("{value:.2?}"),
+        None => "missing".to_string(),
+    };
+    vec![(name, value)]
+}
+
+pub(crate) fn count_rows<F: QueryFilter + 'static>(world: &mut World) -> Vec<(String, String)> {
+    let filter = short_type_name(std::any::type_name::<F>());
+    let mut query = world.query_filtered::<(), F>();
+    vec![(
+        format!("count {filter}"),
+        query.iter(world).count().to_string(),
+    )]
+}
+
+/// Strip the module paths from a type name, so `bevy_ecs::query::filter::With<breakout_clone::Brick>`
+/// becomes `With<Brick>`
+fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+/// The watched values, as of now and as of before the last step
+#[derive(Resource, Default)]
+pub(crate) struct Inspector {
+    watches: Vec<WatchFn>,
+    // set when a step is requested; the step itself only runs during the next frame
+    pub(crate) step_requested: bool,
+    step_running: bool,
+    current: Vec<(String, String)>,
+    previous: Option<Vec<(String, String)>>,
+}
+
+impl Inspector {
+    pub(crate) fn new(watches: Vec<WatchFn>) -> Inspector {
+        Inspector {
+            watches,
+            ..default()
+        }
+    }
+
+    /// Treat the world as having been stepped this frame, because it was changed some other way
+    pub(crate) fn mark_stepped(&mut self) {
+        self.step_running = true;
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct InspectorUi;
+
+#[derive(Component)]
+struct WorldText;
+
+#[derive(Component)]
+struct ChangesText;
+
+const CHANGED_COLOR: Color = Color::srgb(0.1, 0.4, 0.1);
+
+pub(crate) fn build_inspector(mut commands: Commands, inspector: Res<Inspector>) {
+    if inspector.watches.is_empty() {
+        return;
+    }
+
+    let ui = commands
+        .spawn((
+            InspectorUi,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(50.0),
+                right: Val::Px(10.0),
+                max_width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.33)),
+            Visibility::Hidden,
+        ))
+        .id();
+
+    commands.spawn((
+        WorldText,
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(FONT_COLOR),
+        ChildOf(ui),
+    ));
+    commands.spawn((
+        ChangesText,
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(CHANGED_COLOR),
+        ChildOf(ui),
+    ));
+}
+
+/// Refresh the inspector panel, remembering the previous values whenever a step has run.
+///
+/// This is an exclusive system, as the watches can look at any part of the world.
+pub(crate) fn update_inspector(world: &mut World) {
+    let enabled = world.resource::<Stepping>().is_enabled();
+    let mut panels = world.query_filtered::<&mut Visibility, With<InspectorUi>>();
+    for mut visibility in panels.iter_mut(world) {
+        visibility.set_if_neq(if enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+
+    world.resource_scope(|world, mut inspector: Mut<Inspector>| {
+        let inspector = &mut *inspector;
+        let stepped = std::mem::replace(
+            &mut inspector.step_running,
+            std::mem::take(&mut inspector.step_requested),
+        );
+
+        // there's no point in gathering values nobody can see
+        if !enabled {
+            inspector.previous = None;
+            return;
+        }
+
+        let rows: Vec<_> = inspector
+            .watches
+            .clone()
+            .into_iter()
+            .flat_map(|watch| watch(world))
+            .collect();
+        if stepped {
+            inspector.previous = Some(std::mem::replace(&mut inspector.current, rows));
+        } else {
+            inspector.current = rows;
+        }
+
+        let world_text = inspector
+            .current
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let changes_text = match &inspector.previous {
+            None => "Step to see what changes".to_string(),
+            Some(previous) => describe_changes(previous, &inspector.current),
+        };
+
+        let mut texts = world.query::<(&mut Text, Has<WorldText>, Has<ChangesText>)>();
+        for (mut text, is_world, is_changes) in texts.iter_mut(world) {
+            if is_world {
+                text.0.clone_from(&world_text);
+            } else if is_changes {
+                text.0.clone_from(&changes_text);
+            }
+        }
+    });
+}
+
+fn describe_changes(previous: &[(String, String)], current: &[(String, String)]) -> String {
+    let mut changes = Vec::new();
+    for (name, value) in current {
+        match previous.iter().find(|(old_name, _)| old_name == name) {
+            Some((_, old_value)) if old_value != value => {
+                changes.push(format!("{name}: {old_value} -> {value}"));
+            }
+            Some(_) => (),
+            None => changes.push(format!("+ {name}: {value}")),
+        }
+    }
+    for (name, old_value) in previous {
+        if !current.iter().any(|(new_name, _)| new_name == name) {
+            changes.push(format!("- {name}: {old_value}"));
+        }
+    }
+
+    if changes.is_empty() {
+        "Nothing watched changed during the last step".to_string()
+    } else {
+        format!("Changed during the last step:\n{}", changes.join("\n"))
+    }
+}
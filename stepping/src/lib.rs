@@ -0,0 +1,801 @@
+//! A stepping UI for Bevy games, to run their schedules one system or frame at a time.
+//!
+//! Adding a [`SteppingPlugin`] lists the systems of the stepped schedules, and lets you set
+//! breakpoints and break conditions, inspect parts of the world and rewind the recent past.
+//! Stepping needs Bevy's `bevy_debug_stepping` feature, enabled through this crate's feature of
+//! the same name; without it, only a hint explaining how to enable stepping is shown.
+
+pub mod actions;
+mod history;
+mod inspector;
+
+use std::{fmt::Debug, path::PathBuf};
+
+use action_map::{ActionMap, ActionMapConfig, ActionMapPlugin, ActionState};
+use bevy::{
+    app::MainScheduleOrder,
+    ecs::{component::Mutable, query::QueryFilter, schedule::*},
+    prelude::*,
+};
+
+pub use actions::{STEPPING_CONTROLS_PATH, SteppingAction};
+use history::{History, Track};
+use inspector::{Inspector, WatchFn};
+
+/// Independent [`Schedule`] for stepping systems.
+///
+/// The stepping systems must run in their own schedule to be able to inspect
+/// all the other schedules in the [`App`].  This is because the currently
+/// executing schedule is removed from the [`Schedules`] resource while it is
+/// being run.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, ScheduleLabel)]
+struct DebugSchedule;
+
+/// Plugin to add a stepping UI to a game
+pub struct SteppingPlugin {
+    schedule_labels: Vec<InternedScheduleLabel>,
+    conditions: Vec<ConditionSpec>,
+    watches: Vec<WatchFn>,
+    tracks: Vec<Track>,
+    controls_path: PathBuf,
+    menu_key: KeyCode,
+    font_path: String,
+    system_filter: fn(&str) -> bool,
+    add_hint: AddHintFn,
+    top: Val,
+    left: Val,
+}
+
+/// Adds the system spawning the hint, at startup unless set with [`SteppingPlugin::show_hint_in`]
+type AddHintFn = Box<dyn Fn(&mut App) + Send + Sync>;
+
+impl Default for SteppingPlugin {
+    fn default() -> Self {
+        SteppingPlugin {
+            schedule_labels: Vec::new(),
+            conditions: Vec::new(),
+            watches: Vec::new(),
+            tracks: Vec::new(),
+            controls_path: STEPPING_CONTROLS_PATH.into(),
+            menu_key: KeyCode::F2,
+            font_path: FONT_BOLD.to_string(),
+            system_filter: not_bevy_system,
+            add_hint: Box::new(|app| {
+                app.add_systems(Startup, build_stepping_hint.map(drop));
+            }),
+            top: Val::default(),
+            left: Val::default(),
+        }
+    }
+}
+
+/// Bevy's own systems are left out of stepping unless another filter is set
+fn not_bevy_system(name: &str) -> bool {
+    !name.starts_with("bevy")
+}
+
+/// Number of snapshots kept for rewinding, ten seconds of play at the default 64 Hz
+const HISTORY_LENGTH: usize = 640;
+
+/// A break condition registered with [`SteppingPlugin::break_on_event`] or
+/// [`SteppingPlugin::break_on_change`]
+struct ConditionSpec {
+    name: &'static str,
+    // adds the system detecting the condition, given the condition's index in `State::conditions`
+    add_system: fn(&mut App, usize),
+}
+
+impl SteppingPlugin {
+    /// add a schedule to be stepped when stepping is enabled
+    pub fn add_schedule(mut self, label: impl ScheduleLabel) -> SteppingPlugin {
+        self.schedule_labels.push(label.intern());
+        self
+    }
+
+    /// Set the location of the stepping UI when activated
+    pub fn at(self, left: Val, top: Val) -> SteppingPlugin {
+        SteppingPlugin { top, left, ..self }
+    }
+
+    /// Load and save the bindings of the [`SteppingAction`]s from `path`, rather than
+    /// [`STEPPING_CONTROLS_PATH`]
+    pub fn with_controls(self, path: impl Into<PathBuf>) -> SteppingPlugin {
+        SteppingPlugin {
+            controls_path: path.into(),
+            ..self
+        }
+    }
+
+    /// Set the key opening the menu to rebind the stepping controls, F2 by default
+    pub fn with_menu_key(self, menu_key: KeyCode) -> SteppingPlugin {
+        SteppingPlugin { menu_key, ..self }
+    }
+
+    /// Set the font of the stepping UI headings, relative to the assets folder.
+    ///
+    /// The default font is used instead when it can't be loaded.
+    pub fn with_font(self, path: impl Into<String>) -> SteppingPlugin {
+        SteppingPlugin {
+            font_path: path.into(),
+            ..self
+        }
+    }
+
+    /// Only list the systems whose name passes `filter`, the others always run.
+    ///
+    /// By default every system except Bevy's own is listed.
+    pub fn show_systems(self, filter: fn(&str) -> bool) -> SteppingPlugin {
+        SteppingPlugin {
+            system_filter: filter,
+            ..self
+        }
+    }
+
+    /// Only show the hint about the stepping controls in `state`, rather than for the whole run.
+    ///
+    /// The hint is despawned when leaving `state`, which must have scoped entities.
+    pub fn show_hint_in<S: States>(self, state: S) -> SteppingPlugin {
+        SteppingPlugin {
+            add_hint: Box::new(move |app| {
+                let state = state.clone();
+                app.add_systems(
+                    OnEnter(state.clone()),
+                    build_stepping_hint.pipe(
+                        move |In(hint): In<Entity>, mut commands: Commands| {
+                            commands.entity(hint).insert(StateScoped(state.clone()));
+                        },
+                    ),
+                );
+            }),
+            ..self
+        }
+    }
+
+    /// Offer to break at the end of any frame in which an `E` event is sent
+    pub fn break_on_event<E: Event>(mut self, name: &'static str) -> SteppingPlugin {
+        self.conditions.push(ConditionSpec {
+            name,
+            add_system: add_event_condition::<E>,
+        });
+        self
+    }
+
+    /// Offer to break at the end of any frame in which the `R` resource changes
+    pub fn break_on_change<R: Resource>(mut self, name: &'static str) -> SteppingPlugin {
+        self.conditions.push(ConditionSpec {
+            name,
+            add_system: add_change_condition::<R>,
+        });
+        self
+    }
+
+    /// Show the `C` component of every entity matching `F` in the inspector panel
+    pub fn inspect_component<C: Component + Debug, F: QueryFilter + 'static>(
+        mut self,
+    ) -> SteppingPlugin {
+        self.watches.push(inspector::component_rows::<C, F>);
+        self
+    }
+
+    /// Show the value of the `R` resource in the inspector panel
+    pub fn inspect_resource<R: Resource + Debug>(mut self) -> SteppingPlugin {
+        self.watches.push(inspector::resource_rows::<R>);
+        self
+    }
+
+    /// Show how many entities match `F` in the inspector panel
+    pub fn inspect_count<F: QueryFilter + 'static>(mut self) -> SteppingPlugin {
+        self.watches.push(inspector::count_rows::<F>);
+        self
+    }
+
+    /// Record the `C` component of every entity matching `F` after each `FixedUpdate` tick,
+    /// so it can be restored when stepping backward
+    pub fn rewind_component<C, F>(mut self) -> SteppingPlugin
+    where
+        C: Component<Mutability = Mutable> + Clone + PartialEq,
+        F: QueryFilter + 'static,
+    {
+        self.tracks.push(history::component_track::<C, F>());
+        self
+    }
+
+    /// Record the `R` resource after each `FixedUpdate` tick, so it can be restored when
+    /// stepping backward
+    pub fn rewind_resource<R: Resource + Clone + PartialEq>(mut self) -> SteppingPlugin {
+        self.tracks.push(history::resource_track::<R>());
+        self
+    }
+
+    /// Record which entities match `F` after each `FixedUpdate` tick.
+    ///
+    /// When stepping backward, entities spawned since are despawned, and `respawn` is called
+    /// with the [`Transform`] of each entity despawned since. The components recorded with
+    /// [`SteppingPlugin::rewind_component`] are then restored on the entity it returns.
+    pub fn rewind_presence<F: QueryFilter + 'static>(
+        mut self,
+        respawn: fn(&mut Commands, &Transform) -> Entity,
+    ) -> SteppingPlugin {
+        self.tracks.push(history::presence_track::<F>(respawn));
+        self
+    }
+}
+
+impl Plugin for SteppingPlugin {
+    fn build(&self, app: &mut App) {
+        (self.add_hint)(app);
+        if cfg!(not(feature = "bevy_debug_stepping")) {
+            return;
+        }
+
+        app.add_plugins(
+            ActionMapPlugin::<SteppingAction>::new(&self.controls_path)
+                .with_menu_key(self.menu_key),
+        );
+
+        // create and insert our debug schedule into the main schedule order.
+        // We need an independent schedule so we have access to all other
+        // schedules through the `Stepping` resource
+        app.init_schedule(DebugSchedule);
+        let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+        order.insert_after(Update, DebugSchedule);
+
+        // create our stepping resource
+        let mut stepping = Stepping::new();
+        for label in &self.schedule_labels {
+            stepping.add_schedule(*label);
+        }
+        app.insert_resource(stepping);
+
+        // add our startup & stepping systems
+        app.insert_resource(State {
+            ui_top: self.top,
+            ui_left: self.left,
+            font_path: self.font_path.clone(),
+            font: Handle::default(),
+            system_filter: self.system_filter,
+            systems: Vec::new(),
+            selected: 0,
+            conditions: self
+                .conditions
+                .iter()
+                .map(|spec| BreakCondition {
+                    name: spec.name,
+                    enabled: false,
+                    triggered: false,
+                })
+                .collect(),
+            run: None,
+        })
+        .insert_resource(Inspector::new(self.watches.clone()))
+        .insert_resource(History::new(self.tracks.clone(), HISTORY_LENGTH))
+        .add_systems(Startup, inspector::build_inspector)
+        .add_systems(FixedLast, history::record_snapshot)
+        .add_systems(
+            DebugSchedule,
+            (
+                build_ui.run_if(not(initialized)),
+                fall_back_to_default_font.run_if(initialized),
+                handle_input,
+                handle_clicks.run_if(initialized),
+                history::handle_rewind,
+                run_to_break,
+                update_ui.run_if(initialized),
+                history::update_history_text.run_if(initialized),
+                inspector::update_inspector,
+            )
+                .chain(),
+        );
+
+        for (index, spec) in self.conditions.iter().enumerate() {
+            (spec.add_system)(app, index);
+        }
+    }
+}
+
+/// Struct for maintaining stepping state
+#[derive(Resource, Debug)]
+struct State {
+    // every steppable system, in the order they are listed in the UI
+    systems: Vec<SteppableSystem>,
+    // index in `systems` of the system selected with the keyboard
+    selected: usize,
+    conditions: Vec<BreakCondition>,
+    // set while frames are being run until something breaks
+    run: Option<Run>,
+
+    // ui positioning
+    ui_top: Val,
+    ui_left: Val,
+    font_path: String,
+    // font of the UI headings; the default font once `font_path` failed to load
+    font: Handle<Font>,
+    // whether a system is listed in the UI, by name
+    system_filter: fn(&str) -> bool,
+}
+
+#[derive(Debug)]
+struct SteppableSystem {
+    schedule: InternedScheduleLabel,
+    node: NodeId,
+    name: String,
+    breakpoint: bool,
+    // text entity displaying this system
+    text: Entity,
+}
+
+#[derive(Debug)]
+struct BreakCondition {
+    name: &'static str,
+    enabled: bool,
+    // set by the system detecting this condition when it happens
+    triggered: bool,
+}
+
+#[derive(Debug)]
+struct Run {
+    // number of frames continued so far; the cursor may start on a breakpoint
+    frames: u32,
+    // whether to stop at any condition, even the ones which aren't enabled
+    any_condition: bool,
+}
+
+/// condition to check if the stepping UI has been constructed
+fn initialized(state: Res<State>) -> bool {
+    !state.systems.is_empty()
+}
+
+fn add_event_condition<E: Event>(app: &mut App, index: usize) {
+    app.add_systems(
+        DebugSchedule,
+        (move |mut events: EventReader<E>, mut state: ResMut<State>| {
+            if !events.is_empty() {
+                events.clear();
+                state.conditions[index].triggered = true;
+            }
+        })
+        .before(run_to_break),
+    );
+}
+
+fn add_change_condition<R: Resource>(app: &mut App, index: usize) {
+    app.add_systems(
+        DebugSchedule,
+        (move |resource: Res<R>, mut state: ResMut<State>| {
+            if resource.is_changed() && !resource.is_added() {
+                state.conditions[index].triggered = true;
+            }
+        })
+        .before(run_to_break),
+    );
+}
+
+const FONT_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const SELECTED_COLOR: Color = Color::srgb(0.2, 0.2, 0.8);
+const BREAKPOINT_COLOR: Color = Color::srgb(0.8, 0.1, 0.1);
+const FONT_BOLD: &str = "fonts/FiraSans-Bold.ttf";
+
+#[derive(Component)]
+struct SteppingUi;
+
+/// Clicking a system toggles its breakpoint
+#[derive(Component)]
+struct SystemButton(usize);
+
+/// Clicking a condition enables or disables it
+#[derive(Component)]
+struct ConditionButton(usize);
+
+#[derive(Component)]
+struct ConditionText(usize);
+
+/// Construct the stepping UI elements from the [`Schedules`] resource.
+///
+/// This system may run multiple times before constructing the UI as all of the
+/// data may not be available on the first run of the system.  This happens if
+/// one of the stepping schedules has not yet been run.
+fn build_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    schedules: Res<Schedules>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+) {
+    let mut steppable = Vec::new();
+    let mut always_run = Vec::new();
+
+    let Ok(schedule_order) = stepping.schedules() else {
+        return;
+    };
+
+    // go through the stepping schedules and construct a list of systems for
+    // each label
+    for label in schedule_order {
+        let schedule = schedules.get(*label).unwrap();
+
+        // grab the list of systems in the schedule, in the order the
+        // single-threaded executor would run them.
+        let Ok(systems) = schedule.systems() else {
+            return;
+        };
+
+        let mut schedule_systems = Vec::new();
+        for (node_id, system) in systems {
+            // skip filtered out systems, like bevy default systems; we don't want to step those
+            if !(state.system_filter)(&system.name()) {
+                always_run.push((*label, node_id));
+                continue;
+            }
+            schedule_systems.push((node_id, system.name().to_string()));
+        }
+        steppable.push((*label, schedule_systems));
+    }
+
+    for (label, node) in always_run.drain(..) {
+        stepping.always_run_node(label, node);
+    }
+
+    state.font = asset_server.load(&state.font_path);
+
+    let ui = commands
+        .spawn((
+            SteppingUi,
+            Node {
+                position_type: PositionType::Absolute,
+                top: state.ui_top,
+                left: state.ui_left,
+                padding: UiRect::all(Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.33)),
+            Visibility::Hidden,
+        ))
+        .id();
+
+    let mut systems = Vec::new();
+    for (label, schedule_systems) in steppable {
+        commands.spawn((
+            Text(format!("{label:?}")),
+            TextFont {
+                font: state.font.clone(),
+                ..default()
+            },
+            TextColor(FONT_COLOR),
+            ChildOf(ui),
+        ));
+
+        for (node, name) in schedule_systems {
+            // Each system gets its own button, so it can be clicked to toggle a breakpoint
+            let text = commands
+                .spawn((
+                    Button,
+                    SystemButton(systems.len()),
+                    Text::default(),
+                    TextFont::default(),
+                    TextColor(FONT_COLOR),
+                    ChildOf(ui),
+                ))
+                .id();
+
+            systems.push(SteppableSystem {
+                schedule: label,
+                node,
+                name,
+                breakpoint: false,
+                text,
+            });
+        }
+    }
+
+    if !state.conditions.is_empty() {
+        commands.spawn((
+            Text::new("Break conditions"),
+            TextFont {
+                font: state.font.clone(),
+                ..default()
+            },
+            TextColor(FONT_COLOR),
+            ChildOf(ui),
+        ));
+    }
+    for index in 0..state.conditions.len() {
+        commands.spawn((
+            Button,
+            ConditionButton(index),
+            ConditionText(index),
+            Text::default(),
+            TextFont::default(),
+            TextColor(FONT_COLOR),
+            ChildOf(ui),
+        ));
+    }
+
+    history::build_history_text(&mut commands, ui);
+
+    state.systems = systems;
+}
+
+/// The inputs bound to `action`, for the hint
+fn bound_to(action_map: &ActionMap<SteppingAction>, action: SteppingAction) -> String {
+    let bindings = action_map.bindings(action);
+    if bindings.is_empty() {
+        return "unbound".to_string();
+    }
+    bindings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn build_stepping_hint(
+    mut commands: Commands,
+    action_map: Option<Res<ActionMap<SteppingAction>>>,
+    config: Option<Res<ActionMapConfig<SteppingAction>>>,
+) -> Entity {
+    // the controls are only loaded when stepping is supported
+    let hint_text = match (action_map, config) {
+        (Some(action_map), Some(config)) => {
+            let keys = |action| bound_to(&action_map, action);
+            format!(
+                "Press {} to toggle stepping mode ({}: step system, {}: step frame, \
+                 {}/{}: select system, {} or click: toggle breakpoint, \
+                 {}: run to breakpoint, {}: run until any break condition, \
+                 {} and {}: step or scrub through history, {:?}: rebind)",
+                keys(SteppingAction::ToggleStepping),
+                keys(SteppingAction::StepSystem),
+                keys(SteppingAction::StepFrame),
+                keys(SteppingAction::SelectPreviousSystem),
+                keys(SteppingAction::SelectNextSystem),
+                keys(SteppingAction::ToggleBreakpoint),
+                keys(SteppingAction::RunToBreakpoint),
+                keys(SteppingAction::RunUntilCondition),
+                keys(SteppingAction::StepBackward),
+                keys(SteppingAction::StepForward),
+                config.menu_key,
+            )
+        }
+        _ => "Bevy was compiled without stepping support. Run with `--features=bevy_debug_stepping` to enable stepping.".to_string(),
+    };
+    info!("{}", hint_text);
+    // stepping description box
+    commands
+        .spawn((
+            Text::new(hint_text),
+            TextFont {
+                font_size: 15.0,
+                ..default()
+            },
+            TextColor(FONT_COLOR),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(5.0),
+                left: Val::Px(5.0),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+fn toggle_breakpoint(stepping: &mut Stepping, system: &mut SteppableSystem) {
+    system.breakpoint = !system.breakpoint;
+    if system.breakpoint {
+        stepping.set_breakpoint_node(system.schedule, system.node);
+    } else {
+        stepping.clear_breakpoint_node(system.schedule, system.node);
+    }
+}
+
+fn handle_input(
+    actions: Res<ActionState<SteppingAction>>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+    mut inspector: ResMut<Inspector>,
+) {
+    if actions.just_pressed(SteppingAction::PrintStepping) {
+        info!("{:#?}", stepping);
+    }
+    // ToggleStepping turns stepping on or off for the stepped schedules
+    if actions.just_pressed(SteppingAction::ToggleStepping) {
+        if stepping.is_enabled() {
+            stepping.disable();
+            debug!("disabled stepping");
+        } else {
+            stepping.enable();
+            debug!("enabled stepping");
+        }
+        state.run = None;
+    }
+
+    if !stepping.is_enabled() || state.systems.is_empty() {
+        return;
+    }
+
+    // move the selection cursor, wrapping around at either end of the list
+    let system_count = state.systems.len();
+    if actions.just_pressed(SteppingAction::SelectNextSystem) {
+        state.selected = (state.selected + 1) % system_count;
+    } else if actions.just_pressed(SteppingAction::SelectPreviousSystem) {
+        state.selected = (state.selected + system_count - 1) % system_count;
+    }
+
+    if actions.just_pressed(SteppingAction::ToggleBreakpoint) {
+        let selected = state.selected;
+        toggle_breakpoint(&mut stepping, &mut state.systems[selected]);
+    }
+
+    // StepFrame runs the remainder of this frame, StepSystem only the system under the cursor
+    if actions.just_pressed(SteppingAction::StepFrame) {
+        debug!("continue");
+        stepping.continue_frame();
+        inspector.step_requested = true;
+    } else if actions.just_pressed(SteppingAction::StepSystem) {
+        debug!("stepping frame");
+        stepping.step_frame();
+        inspector.step_requested = true;
+    } else if actions.just_pressed(SteppingAction::RunToBreakpoint) {
+        debug!("running to breakpoint");
+        state.run = Some(Run {
+            frames: 0,
+            any_condition: false,
+        });
+    } else if actions.just_pressed(SteppingAction::RunUntilCondition) {
+        debug!("running until any break condition");
+        state.run = Some(Run {
+            frames: 0,
+            any_condition: true,
+        });
+    }
+}
+
+/// Use the default font for the UI headings when their font can't be loaded,
+/// rather than leaving them blank
+fn fall_back_to_default_font(
+    asset_server: Res<AssetServer>,
+    mut state: ResMut<State>,
+    mut fonts: Query<&mut TextFont>,
+) {
+    if !asset_server.load_state(&state.font).is_failed() {
+        return;
+    }
+
+    warn!(
+        "couldn't load {}, the stepping UI uses the default font instead",
+        state.font_path
+    );
+    for mut font in &mut fonts {
+        if font.font == state.font {
+            font.font = default();
+        }
+    }
+    state.font = default();
+}
+
+fn handle_clicks(
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+    system_buttons: Query<(&Interaction, &SystemButton), Changed<Interaction>>,
+    condition_buttons: Query<(&Interaction, &ConditionButton), Changed<Interaction>>,
+) {
+    for (interaction, SystemButton(index)) in &system_buttons {
+        if *interaction == Interaction::Pressed {
+            state.selected = *index;
+            toggle_breakpoint(&mut stepping, &mut state.systems[*index]);
+        }
+    }
+
+    for (interaction, ConditionButton(index)) in &condition_buttons {
+        if *interaction == Interaction::Pressed {
+            let condition = &mut state.conditions[*index];
+            condition.enabled = !condition.enabled;
+        }
+    }
+}
+
+/// Keep continuing frames while running, and break when a breakpoint or condition is hit.
+///
+/// Enabled conditions also break out of normal play, by enabling stepping at the end of the
+/// frame in which they happened.
+fn run_to_break(
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+    mut inspector: ResMut<Inspector>,
+) {
+    let state = &mut *state;
+
+    let mut hit = None;
+    for condition in &mut state.conditions {
+        let wanted = condition.enabled || state.run.as_ref().is_some_and(|run| run.any_condition);
+        if condition.triggered && wanted && hit.is_none() {
+            hit = Some(condition.name);
+        }
+        condition.triggered = false;
+    }
+
+    let Some(run) = &mut state.run else {
+        if let Some(name) = hit
+            && !stepping.is_enabled()
+        {
+            info!("break on {name}");
+            stepping.enable();
+        }
+        return;
+    };
+
+    // The cursor stops in front of breakpoints while continuing, but it can also start on one
+    let at_breakpoint = run.frames > 0
+        && stepping.cursor().is_some_and(|(schedule, node)| {
+            state
+                .systems
+                .iter()
+                .any(|s| s.breakpoint && s.schedule == schedule && s.node == node)
+        });
+
+    if let Some(name) = hit {
+        info!("break on {name}");
+        state.run = None;
+    } else if at_breakpoint {
+        info!("hit breakpoint");
+        state.run = None;
+    } else {
+        stepping.continue_frame();
+        inspector.step_requested = true;
+        run.frames += 1;
+    }
+}
+
+fn update_ui(
+    mut commands: Commands,
+    state: Res<State>,
+    stepping: Res<Stepping>,
+    ui: Single<(Entity, &Visibility), With<SteppingUi>>,
+    mut system_text: Query<(&mut Text, &mut TextColor), Without<ConditionText>>,
+    mut condition_text: Query<(&mut Text, &ConditionText)>,
+) {
+    // ensure the UI is only visible when stepping is enabled
+    let (ui, vis) = *ui;
+    match (vis, stepping.is_enabled()) {
+        (Visibility::Hidden, true) => {
+            commands.entity(ui).insert(Visibility::Inherited);
+        }
+        (Visibility::Hidden, false) | (_, true) => (),
+        (_, false) => {
+            commands.entity(ui).insert(Visibility::Hidden);
+        }
+    }
+
+    // if we're not stepping, there's nothing more to be done here.
+    if !stepping.is_enabled() {
+        return;
+    }
+
+    let cursor = stepping.cursor();
+
+    for (index, system) in state.systems.iter().enumerate() {
+        let Ok((mut text, mut color)) = system_text.get_mut(system.text) else {
+            continue;
+        };
+        let mark = if cursor == Some((system.schedule, system.node)) {
+            "-> "
+        } else {
+            "   "
+        };
+        let breakpoint = if system.breakpoint { "* " } else { "  " };
+        text.0 = format!("{mark}{breakpoint}{}", system.name);
+
+        color.0 = if index == state.selected {
+            SELECTED_COLOR
+        } else if system.breakpoint {
+            BREAKPOINT_COLOR
+        } else {
+            FONT_COLOR
+        };
+    }
+
+    for (mut text, ConditionText(index)) in &mut condition_text {
+        let condition = &state.conditions[*index];
+        let check = if condition.enabled { "[x]" } else { "[ ]" };
+        text.0 = format!("{check} break on {}", condition.name);
+    }
+}